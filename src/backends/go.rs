@@ -0,0 +1,601 @@
+use std::path::{ Path, PathBuf };
+use tera::Context;
+
+use crate::backends::{ mock_names, with_imports, Backend, Project, TestFile, Workflow };
+use crate::domains::file::ProcessedFile;
+use crate::domains::function_signature::{ Dependency, FunctionParameter, FunctionSignature, LiteralType, ParameterType };
+use crate::domains::method::ParsedMethod;
+use crate::domains::schema::{ MethodAndMocks, MockConfig, MockTest };
+use crate::domains::type_definition::{ AliasType, RecordType, SumType };
+use crate::domains::type_mapping::TypeMappings;
+
+
+#[derive(Debug)]
+pub struct Go;
+
+// Go only exports capitalised names, `update_item` becomes `UpdateItem`
+pub fn pascal_case(name: &str) -> String {
+    name.split('_').map(|part| {
+        let mut chars = part.chars();
+        match chars.next() {
+            None => String::new(),
+            Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        }
+    }).collect()
+}
+
+// struct fields too, so `id` becomes `Id`
+fn exported_name(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+    }
+}
+
+// a go string literal holding `text`
+fn go_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// schema defaults are written the Python way, e.g. `'active'` becomes `"active"`
+fn go_value(value: &str) -> String {
+    match value {
+        "None" => String::from("nil"),
+        "True" => String::from("true"),
+        "False" => String::from("false"),
+        quoted if quoted.len() > 1 && (quoted.starts_with('\'') || quoted.starts_with('"')) => go_string(&quoted[1..quoted.len() - 1]),
+        _ => value.to_string(),
+    }
+}
+
+// table fields are unexported, `account_ids` becomes `accountIds`; `name` and `wantErr` belong to the table itself
+fn go_field(name: &str) -> String {
+    let exported = pascal_case(name);
+    let mut chars = exported.chars();
+    let field = match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_lowercase().collect::<String>() + chars.as_str(),
+    };
+    match field.as_str() {
+        "name" | "wantErr" => format!("{}Arg", field),
+        _ => field,
+    }
+}
+
+// one case of a table driven test, defaulted parameters get their default since go has no default arguments
+fn go_table_row(target: &FunctionSignature, name: &str, values: &[(&str, String)], want_err: bool) -> String {
+    let mut fields = vec![format!("name: {}", go_string(name))];
+    fields.extend(target.input.iter().flat_map(|param| {
+        let given = values.iter().find(|(param_name, _)| *param_name == param.name).map(|(_, value)| value.clone());
+        given.or_else(|| param.default.as_deref().map(go_value)).map(|value| format!("{}: {}", go_field(&param.name), value))
+    }));
+    if target.returns_result() {
+        fields.push(format!("wantErr: {}", want_err));
+    }
+    format!("{{{}}},", fields.join(", "))
+}
+
+// the package a go file belongs to is named after its directory
+pub fn package_name(path: &Path) -> String {
+    path.parent().and_then(|parent| parent.file_name()).map(|name| name.to_string_lossy().to_string()).unwrap_or_else(|| String::from("main"))
+}
+
+// a package is imported whole, its functions are qualified by it where they're used
+pub fn import_package(project_name: &str, module: &Path) -> String {
+    format!("import \"{}/{}\"", project_name, module.parent().unwrap_or(module).display())
+}
+
+pub fn import_domain_types(project_name: &str) -> String {
+    format!("import . \"{}/src/domain_types\"", project_name)
+}
+
+pub fn support_imports(project_name: &str, file: &ProcessedFile) -> Vec<String> {
+    if file.list_dependencies().is_empty() {
+        vec![]
+    } else {
+        vec![format!("import \"{}/src/dependencies\"", project_name)]
+    }
+}
+
+// go refuses imports which aren't used, so errors is only imported when a fake returns one
+pub fn test_imports(tests: &[String]) -> Vec<String> {
+    let mut imports = vec![];
+    if tests.iter().any(|test| test.contains("errors.")) {
+        imports.push(String::from("import \"errors\""));
+    }
+    imports.push(String::from("import \"testing\""));
+    imports
+}
+
+// a row of the target's table, the fake for this dependency fails the way the scenario describes
+pub fn table_row(test: &MockTest, mock_config: &MockConfig, target: &FunctionSignature) -> String {
+    let values: Vec<(&str, String)> = target.input.iter().filter_map(|param| match &param.ptype {
+        ParameterType::Dependency(dep) if dep.dependency_name == mock_config.name => {
+            Some((param.name.as_str(), format!("fakes.{}{{{}}}", dep.trait_name(), test.mock_response)))
+        },
+        _ => None,
+    }).collect();
+    go_table_row(target, &test.description, &values, true)
+}
+
+// each fake answers every method of its dependency's interface with its Response and Err
+pub fn mock_implementations(dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+    dependencies.iter().flat_map(|(dependency, methods)| {
+        let name = dependency.trait_name();
+        methods.iter().map(move |method| {
+            format!("func (f {}) {}(request map[string]any) (map[string]any, error) {{\n    return f.Response, f.Err\n}}", name, pascal_case(method))
+        }).collect::<Vec<String>>()
+    }).collect()
+}
+
+// the workflow written out a step at a time, returning the first error
+#[derive(Debug)]
+pub struct Handler {
+    pub imports: Vec<String>,
+    pub event_type: String,
+    pub output_type: String,
+    pub declares_zero: bool,
+    pub calls: Vec<String>,
+    pub final_value: String,
+}
+
+impl Go {
+    // go has no default arguments, so keep the value visible for whoever implements the stub
+    fn render_parameter(&self, param: &FunctionParameter, mappings: &TypeMappings) -> String {
+        match &param.default {
+            Some(default) => format!("{} {} /* = {} */", param.name, self.render_type(&param.ptype, mappings), default),
+            None => format!("{} {}", param.name, self.render_type(&param.ptype, mappings)),
+        }
+    }
+
+    // one test function per method: a row for each fault scenario of its dependencies and each literal value
+    pub fn table_driven_test(&self, method_and_mocks: &MethodAndMocks, mappings: &TypeMappings) -> Option<String> {
+        let target = &method_and_mocks.method.ast;
+        let mut rows: Vec<String> = method_and_mocks.mocks.iter()
+            .flat_map(|config| config.tests.iter().map(move |test| self.render_mock_test(test, &[], config, target)))
+            .collect();
+        for literal_param in &target.input {
+            if let ParameterType::LiteralType(literal) = &literal_param.ptype {
+                rows.extend(literal.values.iter().map(|value| {
+                    go_table_row(target, &format!("accepts {} as {}", value.replace("'", ""), literal_param.name), &[(&literal_param.name, go_value(value))], false)
+                }));
+                let invalid = go_value(&literal.invalid_value());
+                rows.push(go_table_row(target, &format!("rejects an invalid {}", literal_param.name), &[(&literal_param.name, invalid)], true));
+            }
+        }
+        if rows.is_empty() {
+            return None;
+        }
+
+        let function_name = pascal_case(&target.name);
+        let mut fields = vec![String::from("name string")];
+        fields.extend(target.input.iter().map(|param| {
+            let field_type = match &param.ptype {
+                ParameterType::Dependency(dep) => format!("fakes.{}", dep.trait_name()),
+                ptype => self.render_type(ptype, mappings),
+            };
+            format!("{} {}", go_field(&param.name), field_type)
+        }));
+        let arguments = target.input.iter().map(|param| format!("tt.{}", go_field(&param.name))).collect::<Vec<String>>().join(", ");
+        let check = if target.returns_result() {
+            fields.push(String::from("wantErr bool"));
+            format!("_, err := {}({})\n            if (err != nil) != tt.wantErr {{\n                t.Errorf(\"{}() error = %v, wantErr %v\", err, tt.wantErr)\n            }}",
+                function_name, arguments, function_name)
+        } else if self.render_type(&target.output, mappings).is_empty() {
+            format!("{}({})\n            t.Fatal(\"add assertions\")", function_name, arguments)
+        } else {
+            format!("_ = {}({})\n            t.Fatal(\"add assertions\")", function_name, arguments)
+        };
+
+        let fields: String = fields.iter().map(|field| format!("        {}\n", field)).collect();
+        let rows: String = rows.iter().map(|row| format!("        {}\n", row)).collect();
+        Some(format!("func Test{}(t *testing.T) {{\n    tests := []struct {{\n{}    }}{{\n{}    }}\n    for _, tt := range tests {{\n        t.Run(tt.name, func(t *testing.T) {{\n            {}\n        }})\n    }}\n}}",
+            function_name, fields, rows, check))
+    }
+
+    // Handler calls each workflow step in turn, passing a step its dependencies and defaults. The value so far
+    // goes to the first parameter left and any after that get their zero value.
+    pub fn handler(&self, project_name: &str, steps: &[(&ProcessedFile, &ParsedMethod)], mappings: &TypeMappings) -> Handler {
+        let mut imports: Vec<String> = vec![];
+        for (file, _) in steps {
+            let package = import_package(project_name, &file.path);
+            if !imports.contains(&package) {
+                imports.push(package);
+            }
+        }
+        if steps.iter().any(|(_, method)| !method.list_dependencies().is_empty()) {
+            imports.push(format!("import \"{}/src/dependencies\"", project_name));
+        }
+
+        let event_type = steps.first()
+            .and_then(|(_, method)| method.ast.input.iter().find(|param| !matches!(param.ptype, ParameterType::Dependency(_)) && param.default.is_none()))
+            .map(|param| self.render_type(&param.ptype, mappings))
+            .unwrap_or_else(|| String::from("any"));
+        let mut value = String::from("event");
+        let mut output_type = String::new();
+        let mut calls = vec![];
+        for (index, (file, method)) in steps.iter().enumerate() {
+            let mut piped = false;
+            let arguments = method.ast.input.iter().map(|param| match (&param.ptype, &param.default) {
+                (ParameterType::Dependency(dep), _) => dep.dependency_name.clone(),
+                (_, Some(default)) => go_value(default),
+                (ptype, None) if piped => format!("*new({})", self.render_type(ptype, mappings)),
+                (_, None) => {
+                    piped = true;
+                    value.clone()
+                },
+            }).collect::<Vec<String>>().join(", ");
+            let call = format!("{}.{}({})", package_name(&file.path), pascal_case(&method.ast.name), arguments);
+            let step_value = format!("value{}", index + 1);
+            let result_children = match &method.ast.output {
+                ParameterType::ApplicationParentType(output) if output.type_name == "Result" && output.children.len() == 2 => Some(&output.children),
+                _ => None,
+            };
+            match result_children {
+                Some(children) => {
+                    output_type = self.render_type(&children[0], mappings);
+                    calls.push(format!("    {}, err := {}\n    if err != nil {{\n        return zero, err\n    }}", step_value, call));
+                    value = step_value;
+                },
+                None if self.render_type(&method.ast.output, mappings).is_empty() => {
+                    calls.push(format!("    {}", call));
+                },
+                None => {
+                    output_type = self.render_type(&method.ast.output, mappings);
+                    calls.push(format!("    {} := {}", step_value, call));
+                    value = step_value;
+                },
+            }
+        }
+        // the handler returns what the workflow ends with, if the last step produces anything
+        let ends_with_value = steps.last().is_some_and(|(_, method)| !self.render_type(&method.ast.output, mappings).is_empty());
+        if !ends_with_value {
+            output_type = String::new();
+        }
+        let uses_zero = steps.iter().any(|(_, method)| method.ast.returns_result());
+        let calls: Vec<String> = calls.into_iter().map(|call| if output_type.is_empty() { call.replace("return zero, err", "return err") } else { call }).collect();
+
+        Handler {
+            imports,
+            event_type,
+            declares_zero: uses_zero && !output_type.is_empty(),
+            output_type,
+            calls,
+            final_value: value,
+        }
+    }
+}
+
+impl Backend for Go {
+    fn language(&self) -> &'static str {
+        "go"
+    }
+    fn file_extension(&self) -> &'static str {
+        "go"
+    }
+    fn template_set(&self) -> &'static str {
+        "go"
+    }
+    fn mock_listing_set(&self) -> &'static str {
+        "go"
+    }
+    // a go function returns its error beside the value, so needs no result file
+    fn project_files(&self) -> &'static [(&'static str, &'static str)] {
+        &[("go_mod.hbs", "go.mod")]
+    }
+    fn main_file(&self) -> &'static str {
+        "main.go"
+    }
+    // a go directory is a package, so the types get one of their own
+    fn domain_types_file(&self) -> String {
+        String::from("src/domain_types/domain_types.go")
+    }
+    fn dependencies_file(&self) -> String {
+        String::from("src/dependencies/dependencies.go")
+    }
+    // go tests live beside the code in each package, so the fakes need a package of their own
+    fn mocks_file(&self) -> String {
+        String::from("src/dependencies/fakes/fakes.go")
+    }
+    // a package is its directory, nothing has to declare it
+    fn module_declarations(&self, _filename: &Path) -> Vec<(PathBuf, String)> {
+        vec![]
+    }
+
+    fn applied_type(&self, constructor: &str, arguments: &[String]) -> String {
+        format!("{}[{}]", constructor, arguments.join(", "))
+    }
+    fn optional_type(&self, inner: &str) -> String {
+        format!("*{}", inner)
+    }
+    // Go has no union types, the implementation has to type switch on the value
+    fn union_type(&self, _members: &[String]) -> String {
+        String::from("any")
+    }
+    fn tuple_type(&self, members: &[String]) -> String {
+        let fields = members.iter().enumerate().map(|(index, member)| format!("F{} {}", index, member)).collect::<Vec<String>>().join("; ");
+        format!("struct{{ {} }}", fields)
+    }
+    fn function_type(&self, params: &[String], output: &str) -> String {
+        format!("func({}) {}", params.join(", "), output)
+    }
+    fn literal_type(&self, literal: &LiteralType) -> String {
+        if literal.is_numeric() { String::from("int") } else { String::from("string") }
+    }
+    // the interface declared in the generated dependencies package
+    fn dependency_type(&self, dependency: &Dependency) -> String {
+        format!("dependencies.{}", dependency.trait_name())
+    }
+
+    fn declare_record(&self, record: &RecordType, mappings: &TypeMappings) -> String {
+        let fields: String = record.fields.iter()
+            .map(|field| format!("    {} {} `json:\"{}\"`\n", exported_name(&field.name), self.render_type(&field.ftype, mappings), field.name))
+            .collect();
+        format!("type {} struct {{\n{}}}\n", record.name, fields)
+    }
+    // Go has no sum types, so each variant is a struct implementing a marker interface
+    fn declare_sum(&self, sum: &SumType, mappings: &TypeMappings) -> String {
+        let marker = format!("is{}", sum.name);
+        let variants: String = sum.variants.iter()
+            .map(|variant| format!("\n{}\nfunc ({}) {}() {{}}\n", self.declare_record(variant, mappings), variant.name, marker))
+            .collect();
+        format!("type {} interface {{\n    {}()\n}}\n{}", sum.name, marker, variants)
+    }
+    fn declare_alias(&self, alias: &AliasType, mappings: &TypeMappings) -> String {
+        format!("type {} = {}\n", alias.name, self.render_type(&alias.target, mappings))
+    }
+    fn render_function(&self, signature: &FunctionSignature, mappings: &TypeMappings) -> String {
+        let parameters = signature.input.iter().map(|param| self.render_parameter(param, mappings)).collect::<Vec<String>>().join(", ");
+        let type_parameters = if signature.type_parameters.is_empty() {
+            String::new()
+        } else {
+            let constrained = signature.type_parameters.iter().map(|type_parameter| format!("{} any", type_parameter)).collect::<Vec<String>>();
+            format!("[{}]", constrained.join(", "))
+        };
+        // a function returning nothing has no result type at all
+        let output = match self.render_type(&signature.output, mappings) {
+            output if output.is_empty() => String::new(),
+            output => format!("{} ", output),
+        };
+        format!("{}func {}{}({}) {}{{\n    panic(\"not implemented\")\n}}\n",
+            signature.doc_comment("// "), pascal_case(&signature.name), type_parameters, parameters, output)
+    }
+
+    fn domain_types_module(&self, definitions: &[String]) -> String {
+        format!("package domain_types\n\n{}", definitions.join("\n"))
+    }
+    // each directory is a package, named after it
+    fn application_module(&self, project: &Project, file: &ProcessedFile) -> String {
+        let functions = file.methods.iter().map(|method| self.render_function(&method.ast, project.mappings)).collect::<Vec<String>>().join("\n");
+
+        let mut imports: Vec<String> = vec![];
+        if project.uses_declared_types(file.methods.iter()) {
+            imports.push(import_domain_types(project.name));
+        }
+        imports.extend(support_imports(project.name, file));
+        imports.extend(file.list_type_modules().iter().map(|module_path| format!("import \"{}\"", module_path.join("/"))));
+        format!("package {}\n\n{}", package_name(&file.path), with_imports(&imports, functions))
+    }
+    // a small interface per dependency and a client to fill in
+    fn dependency_declarations(&self, dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+        dependencies.iter().map(|(dependency, methods)| {
+            let name = dependency.trait_name();
+            let declarations: String = methods.iter().map(|method| format!("    {}(request map[string]any) (map[string]any, error)\n", pascal_case(method))).collect();
+            let implementations: String = methods.iter()
+                .map(|method| format!("\nfunc ({}Client) {}(request map[string]any) (map[string]any, error) {{\n    panic(\"not implemented\")\n}}\n", name, pascal_case(method)))
+                .collect();
+            format!("// {} is what the application needs from {}\ntype {} interface {{\n{}}}\n\n// {}Client talks to the real service\ntype {}Client struct{{}}\n{}",
+                name, dependency.dependency_name, name, declarations, name, name, implementations)
+        }).collect()
+    }
+    fn mock_list(&self, configs: &[&MockConfig], dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+        let mut mock_list: Vec<String> = configs.iter().map(|config| config.mock.clone()).collect();
+        mock_list.extend(mock_implementations(dependencies));
+        mock_list
+    }
+
+    fn render_mock_test(&self, test: &MockTest, _mock_refs: &[String], mock_config: &MockConfig, target: &FunctionSignature) -> String {
+        table_row(test, mock_config, target)
+    }
+    // literal values become rows of the table, and defaults are always passed
+    fn test_module(&self, project: &Project, file: &ProcessedFile, methods_and_mocks: &[MethodAndMocks], _is_main: bool) -> Option<TestFile> {
+        let tests: Vec<String> = methods_and_mocks.iter().flat_map(|method_and_mocks| self.table_driven_test(method_and_mocks, project.mappings)).collect();
+        // an empty test file wouldn't compile, its testing import would go unused
+        if tests.is_empty() {
+            return None;
+        }
+
+        let mut imports = test_imports(&tests);
+        let tested = methods_and_mocks.iter().filter(|method_and_mocks| {
+            !method_and_mocks.mocks.is_empty() || method_and_mocks.method.ast.input.iter().any(|param| matches!(param.ptype, ParameterType::LiteralType(_)))
+        });
+        if project.uses_declared_types(tested.map(|method_and_mocks| &method_and_mocks.method)) {
+            imports.push(import_domain_types(project.name));
+        }
+        if !mock_names(file).is_empty() {
+            imports.push(format!("import \"{}/src/dependencies/fakes\"", project.name));
+        }
+        // go tests sit beside the code, in the same package
+        let mut test_file = TestFile::new(format!("{}_test.go", file.path.display()), &imports, &[], &tests);
+        test_file.context.insert("package", &package_name(&file.path));
+        Some(test_file)
+    }
+    // the workflow lives in package main, which the generated tests can't import
+    fn main_test(&self, _project: &Project, _mock_names: &[String]) -> Option<TestFile> {
+        None
+    }
+    fn main_context(&self, project: &Project, workflow: &Workflow) -> Context {
+        let mut handler = self.handler(project.name, &workflow.steps, project.mappings);
+        if project.uses_declared_types(workflow.steps.iter().map(|(_, method)| *method)) {
+            handler.imports.push(import_domain_types(project.name));
+        }
+        // only the dependencies the workflow uses, go refuses unused variables
+        let dependencies = workflow.constructors(workflow.steps.iter().flat_map(|(_, method)| method.list_dependencies()));
+
+        let mut context = Context::new();
+        context.insert("imports", &handler.imports);
+        context.insert("dependencies", &dependencies);
+        context.insert("event_type", &handler.event_type);
+        context.insert("output_type", &handler.output_type);
+        context.insert("declares_zero", &handler.declares_zero);
+        context.insert("workflow_calls", &handler.calls);
+        context.insert("final_value", &handler.final_value);
+        context
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature_parser::{ parse_signature, parse_type_definition };
+
+    fn method(signature: &str) -> ParsedMethod {
+        ParsedMethod { raw: signature.to_string(), ast: parse_signature(signature).unwrap() }
+    }
+
+    fn os_fake() -> MockConfig {
+        MockConfig {
+            name: String::from("os"),
+            import_statement: String::new(),
+            imports: vec![],
+            methods: vec![String::from("environ")],
+            mock: String::new(),
+            tests: vec![MockTest { description: String::from("handle missing env var"), mock_response: String::from("Err: errors.New(\"missing\")") }],
+        }
+    }
+
+    #[test]
+    fn render_function_test() {
+        let render = |signature: &str| Go.render_function(&parse_signature(signature).unwrap(), &TypeMappings::standard());
+        assert_eq!(
+            render("get_account(id: AccountId, limit: Int = 100) -> Result[Account, ErrorMsg]"),
+            "func GetAccount(id AccountId, limit int /* = 100 */) (Account, error) {\n    panic(\"not implemented\")\n}\n"
+        );
+        assert_eq!(render("first<T>(items: List[T]) -> Optional[T]"), "func First[T any](items []T) *T {\n    panic(\"not implemented\")\n}\n");
+        assert_eq!(
+            render("#: Stores the users\npersist_users(dynamo: {dynamodb}, users: List[User]) -> None"),
+            "// Stores the users\nfunc PersistUsers(dynamo dependencies.Dynamodb, users []User) {\n    panic(\"not implemented\")\n}\n"
+        );
+    }
+
+    #[test]
+    fn render_type_test() {
+        let signature = parse_signature("f(a: User?, b: User | Admin, c: (AccountId, User), d: Literal['active'], e: Literal[1, 2], f: (AccountId) -> User) -> None").unwrap();
+        let mappings = TypeMappings::standard();
+        let types: Vec<String> = signature.input.iter().map(|param| Go.render_type(&param.ptype, &mappings)).collect();
+        assert_eq!(types, vec!["*User", "any", "struct{ F0 AccountId; F1 User }", "string", "int", "func(AccountId) User"]);
+    }
+
+    #[test]
+    fn declare_types_test() {
+        let declare = |definition: &str| Go.render_type_definition(&parse_type_definition(definition).unwrap(), &TypeMappings::standard());
+        assert_eq!(declare("User { id: UserId, email: Email }"), "type User struct {\n    Id UserId `json:\"id\"`\n    Email Email `json:\"email\"`\n}\n");
+        assert_eq!(
+            declare("Status = Active | Disabled { reason: String }"),
+            "type Status interface {\n    isStatus()\n}\n\ntype Active struct {\n}\n\nfunc (Active) isStatus() {}\n\ntype Disabled struct {\n    Reason string `json:\"reason\"`\n}\n\nfunc (Disabled) isStatus() {}\n"
+        );
+        assert_eq!(declare("UserIds = List[UserId]"), "type UserIds = []UserId\n");
+    }
+
+    #[test]
+    fn go_value_test() {
+        assert_eq!(go_value("'active'"), "\"active\"");
+        assert_eq!(go_value(r#"'say "hi" \ bye'"#), r#""say \"hi\" \\ bye""#);
+        assert_eq!(go_value("None"), "nil");
+    }
+
+    #[test]
+    fn go_mock_test() {
+        let target = parse_signature("get_account(os: {os}, account_id: AccountId, name: String = 'main', limit: Int = 100) -> Result[Account, ErrorMsg]").unwrap();
+        let test = MockTest { description: String::from("handle missing env var"), mock_response: String::from("Err: errors.New(\"missing\")") };
+        assert_eq!(
+            Go.render_mock_test(&test, &[], &os_fake(), &target),
+            "{name: \"handle missing env var\", os: fakes.Os{Err: errors.New(\"missing\")}, nameArg: \"main\", limit: 100, wantErr: true},"
+        )
+    }
+
+    #[test]
+    fn renders_table_driven_test() {
+        let method_and_mocks = MethodAndMocks {
+            method: method("set_status(os: {os}, status: Literal['active', 'disabled'], limit: Int = 100) -> Result[Account, ErrorMsg]"),
+            mocks: vec![os_fake()],
+        };
+        let expected = r#"func TestSetStatus(t *testing.T) {
+    tests := []struct {
+        name string
+        os fakes.Os
+        status string
+        limit int
+        wantErr bool
+    }{
+        {name: "handle missing env var", os: fakes.Os{Err: errors.New("missing")}, limit: 100, wantErr: true},
+        {name: "accepts active as status", status: "active", limit: 100, wantErr: false},
+        {name: "accepts disabled as status", status: "disabled", limit: 100, wantErr: false},
+        {name: "rejects an invalid status", status: "__invalid__", limit: 100, wantErr: true},
+    }
+    for _, tt := range tests {
+        t.Run(tt.name, func(t *testing.T) {
+            _, err := SetStatus(tt.os, tt.status, tt.limit)
+            if (err != nil) != tt.wantErr {
+                t.Errorf("SetStatus() error = %v, wantErr %v", err, tt.wantErr)
+            }
+        })
+    }
+}"#;
+        assert_eq!(Go.table_driven_test(&method_and_mocks, &TypeMappings::standard()), Some(String::from(expected)));
+        let untested = MethodAndMocks { method: method("count_users(users: List[User]) -> Int"), mocks: vec![] };
+        assert_eq!(Go.table_driven_test(&untested, &TypeMappings::standard()), None);
+    }
+
+    #[test]
+    fn handler_test() {
+        let validator = ProcessedFile::new(PathBuf::from("src/validator"), vec![method("validate_input(input: DynamoStreamEvent) -> Result[List[AccountId], ErrorMsg]")]);
+        let users = ProcessedFile::new(PathBuf::from("src/domains/users"), vec![
+            method("get_users(http_client: {requests}, account_ids: List[AccountId], limit: Int = 100) -> List[User]"),
+            method("persist_users(users: List[User]) -> None"),
+        ]);
+        let steps = [(&validator, &validator.methods[0]), (&users, &users.methods[0])];
+        let workflow = Go.handler("my_project", &steps, &TypeMappings::standard());
+        assert_eq!(workflow.imports, vec!["import \"my_project/src\"", "import \"my_project/src/domains\"", "import \"my_project/src/dependencies\""]);
+        assert_eq!(workflow.event_type, "DynamoStreamEvent");
+        assert_eq!(workflow.output_type, "[]User");
+        assert!(workflow.declares_zero);
+        assert_eq!(workflow.calls, vec![
+            "    value1, err := src.ValidateInput(event)\n    if err != nil {\n        return zero, err\n    }",
+            "    value2 := domains.GetUsers(requests, value1, 100)",
+        ]);
+        assert_eq!(workflow.final_value, "value2");
+
+        // a workflow ending in a step without an output only returns the error
+        let steps = [(&validator, &validator.methods[0]), (&users, &users.methods[0]), (&users, &users.methods[1])];
+        let workflow = Go.handler("my_project", &steps, &TypeMappings::standard());
+        assert_eq!(workflow.output_type, "");
+        assert!(!workflow.declares_zero);
+        assert_eq!(workflow.calls[0], "    value1, err := src.ValidateInput(event)\n    if err != nil {\n        return err\n    }");
+        assert_eq!(workflow.calls[2], "    domains.PersistUsers(value2)");
+    }
+
+    #[test]
+    fn dependency_declarations_test() {
+        let dependencies = vec![(Dependency { dependency_name: String::from("dynamodb"), methods: vec![] }, vec![String::from("update_item")])];
+        let interface = r#"// Dynamodb is what the application needs from dynamodb
+type Dynamodb interface {
+    UpdateItem(request map[string]any) (map[string]any, error)
+}
+
+// DynamodbClient talks to the real service
+type DynamodbClient struct{}
+
+func (DynamodbClient) UpdateItem(request map[string]any) (map[string]any, error) {
+    panic("not implemented")
+}
+"#;
+        assert_eq!(Go.dependency_declarations(&dependencies), vec![interface]);
+        assert_eq!(
+            mock_implementations(&dependencies),
+            vec!["func (f Dynamodb) UpdateItem(request map[string]any) (map[string]any, error) {\n    return f.Response, f.Err\n}"]
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use std::path::{ Path, PathBuf };
+use tera::Context;
+
+use crate::backends::{
+    default_argument_test, literal_tests, mentions_type, mock_imports, mock_names, mock_tests, relative_module_path, test_path, with_imports,
+    Arguments, Backend, CallTests, Project, TestFile, Workflow
+};
+use crate::domains::file::ProcessedFile;
+use crate::domains::function_signature::{ Dependency, FunctionParameter, FunctionSignature, LiteralType, ParameterType };
+use crate::domains::method::ParsedMethod;
+use crate::domains::schema::{ create_mock_name, MethodAndMocks, MockConfig, MockTest };
+use crate::domains::type_definition::{ AliasType, RecordType, SumType };
+use crate::domains::type_mapping::TypeMappings;
+
+
+#[derive(Debug)]
+pub struct JavaScript;
+
+// schemas write defaults the Python way, JavaScript spells the constants differently
+pub fn javascript_value(value: &str) -> &str {
+    match value {
+        "None" => "null",
+        "True" => "true",
+        "False" => "false",
+        _ => value,
+    }
+}
+
+// the description followed by any tags such as `@param`
+pub fn jsdoc(signature: &FunctionSignature, tags: &[String]) -> String {
+    if signature.description.is_none() && tags.is_empty() {
+        return String::new();
+    }
+    let tags: String = tags.iter().map(|tag| format!(" * {}\n", tag)).collect();
+    format!("/**\n{}{} */\n", signature.doc_comment(" * "), tags)
+}
+
+// mocks stand in for the dependencies, defaulted parameters are left out and everything else gets a placeholder
+fn javascript_call_arguments(target: &FunctionSignature, placeholder: &str) -> String {
+    target.input.iter().filter(|param| param.default.is_none()).map(|param| match &param.ptype {
+        ParameterType::Dependency(dep) => create_mock_name(&dep.dependency_name),
+        _ => String::from(placeholder),
+    }).collect::<Vec<String>>().join(", ")
+}
+
+// a jest case which spies on the mocked methods before calling the target, restoreAllMocks puts them back
+pub fn jest_mock_test(test: &MockTest, mock_refs: &[String], target: &FunctionSignature, placeholder: &str) -> String {
+    let (async_prefix, await_prefix) = if target.is_async { ("async ", "await ") } else { ("", "") };
+    let patches: String = mock_refs.iter()
+        .map(|mock_ref| {
+            let (object, method) = mock_ref.rsplit_once('.').unwrap_or(("", mock_ref));
+            format!("\n    jest.spyOn({}, '{}').{};", object, method, test.mock_response)
+        })
+        .collect();
+    format!("it('{}', {}() => {{{}\n    const result = {}{}({});\n    expect(1).toBe(2);\n}});",
+        test.description, async_prefix, patches, await_prefix, target.name, javascript_call_arguments(target, placeholder))
+}
+
+pub fn jest_call_test(description: &str, target: &FunctionSignature, arguments: &str) -> String {
+    let (async_prefix, await_prefix) = if target.is_async { ("async ", "await ") } else { ("", "") };
+    format!("it('{}', {}() => {{\n    const result = {}{}({});\n    expect(1).toBe(2);\n}});",
+        description, async_prefix, await_prefix, target.name, arguments)
+}
+
+// jest refuses a suite without tests, so the handler gets one to fill in
+pub fn render_handler_test() -> String {
+    String::from("it('handles an event', async () => {\n    const response = await handler({}, {});\n    expect(response.statusCode).toBe(200);\n});")
+}
+
+// arguments are positional, so a defaulted parameter the case skips over is passed as `undefined`
+pub fn jest_argument(param: &FunctionParameter, given: Option<String>, placeholder: &str) -> String {
+    match (given, &param.ptype) {
+        (Some(value), _) => value,
+        (None, ParameterType::Dependency(dep)) => create_mock_name(&dep.dependency_name),
+        (None, _) if param.default.is_some() => String::from("undefined"),
+        _ => String::from(placeholder),
+    }
+}
+
+pub fn import_module(module_path: &[String]) -> String {
+    format!("const {} = require('{}');", module_path.last().cloned().unwrap_or_default(), module_path.join("/"))
+}
+
+// `names` from the module at `module`, e.g. `src/domains/user`, required from a file in `from_dir`
+pub fn import_functions(from_dir: &Path, module: &Path, names: &[String]) -> String {
+    format!("const {{ {} }} = require('{}');", names.join(", "), relative_module_path(from_dir, module))
+}
+
+// functions with dependencies are imported under another name, the partially applied ones take theirs
+pub fn import_workflow_functions(file: &ProcessedFile) -> String {
+    let names: Vec<String> = file.methods.iter().map(|method| {
+        if method.list_dependencies().is_empty() {
+            method.ast.name.clone()
+        } else {
+            format!("{}: {}_unbound", method.ast.name, method.ast.name)
+        }
+    }).collect();
+    import_functions(Path::new("src"), &file.path, &names)
+}
+
+pub fn bind_dependencies(method: &ParsedMethod) -> String {
+    let dependency_names: Vec<String> = method.list_dependencies().iter().map(|dep| dep.dependency_name.clone()).collect();
+    format!("const {} = {}_unbound.bind(null, {});\n", method.ast.name, method.ast.name, dependency_names.join(", "))
+}
+
+// the typedefs are only read by editors and checkers
+pub fn import_domain_types(from_dir: &Path, type_names: &[&str]) -> String {
+    let module = relative_module_path(from_dir, Path::new("src/domain_types"));
+    type_names.iter()
+        .map(|name| format!("/** @typedef {{import('{}').{}}} {} */", module, name, name))
+        .collect::<Vec<String>>().join("\n")
+}
+
+// the generic Result typedef declared in src/result.js
+pub fn import_result(from_dir: &Path) -> String {
+    let result = relative_module_path(from_dir, Path::new("src/result"));
+    format!("/**\n * @template T, E\n * @typedef {{import('{}').Result<T, E>}} Result\n */", result)
+}
+
+// the dependency typedefs an application file's JSDoc names, and Result when a signature uses it
+pub fn support_imports(file: &ProcessedFile) -> Vec<String> {
+    let from_dir = file.path.parent().unwrap_or(&file.path);
+    let dependencies = relative_module_path(from_dir, Path::new("src/dependencies"));
+    let mut imports: Vec<String> = file.list_dependency_types().iter()
+        .map(|name| format!("/** @typedef {{import('{}').{}}} {} */", dependencies, name, name))
+        .collect();
+    if file.methods.iter().any(|method| method.ast.uses_type("Result")) {
+        imports.push(import_result(from_dir));
+    }
+    imports
+}
+
+impl JavaScript {
+    // the type goes in the JSDoc above the function
+    fn render_parameter(&self, param: &FunctionParameter) -> String {
+        match &param.default {
+            Some(default) => format!("{} = {}", param.name, javascript_value(default)),
+            None => param.name.clone(),
+        }
+    }
+
+    // `@param {number} [limit=100]`, optional parameters are bracketed with their default
+    fn jsdoc_tag(&self, param: &FunctionParameter, mappings: &TypeMappings) -> String {
+        let name = match &param.default {
+            Some(default) => format!("[{}={}]", param.name, javascript_value(default)),
+            None => param.name.clone(),
+        };
+        format!("@param {{{}}} {}", self.render_type(&param.ptype, mappings), name)
+    }
+}
+
+impl Backend for JavaScript {
+    fn language(&self) -> &'static str {
+        "javascript"
+    }
+    fn file_extension(&self) -> &'static str {
+        "js"
+    }
+    fn template_set(&self) -> &'static str {
+        "javascript"
+    }
+    fn mock_listing_set(&self) -> &'static str {
+        "javascript"
+    }
+    fn project_files(&self) -> &'static [(&'static str, &'static str)] {
+        &[("package.hbs", "package.json"), ("result.hbs", "src/result.js")]
+    }
+    fn main_file(&self) -> &'static str {
+        "src/index.js"
+    }
+    // javascript modules declare their exports themselves, so need no marker file
+    fn module_declarations(&self, _filename: &Path) -> Vec<(PathBuf, String)> {
+        vec![]
+    }
+
+    fn optional_type(&self, inner: &str) -> String {
+        format!("{} | undefined", inner)
+    }
+    fn union_type(&self, members: &[String]) -> String {
+        members.join(" | ")
+    }
+    fn tuple_type(&self, members: &[String]) -> String {
+        format!("[{}]", members.join(", "))
+    }
+    fn function_type(&self, params: &[String], output: &str) -> String {
+        format!("function({}): {}", params.join(", "), output)
+    }
+    fn literal_type(&self, literal: &LiteralType) -> String {
+        literal.values.join(" | ")
+    }
+    // the typedef declared in the generated dependencies module
+    fn dependency_type(&self, dependency: &Dependency) -> String {
+        dependency.trait_name()
+    }
+
+    fn declare_record(&self, record: &RecordType, mappings: &TypeMappings) -> String {
+        let properties: String = record.fields.iter()
+            .map(|field| format!(" * @property {{{}}} {}\n", self.render_type(&field.ftype, mappings), field.name))
+            .collect();
+        format!("/**\n * @typedef {{Object}} {}\n{} */\n", record.name, properties)
+    }
+    fn declare_sum(&self, sum: &SumType, mappings: &TypeMappings) -> String {
+        let variants: String = sum.variants.iter().map(|variant| format!("{}\n", self.declare_record(variant, mappings))).collect();
+        let names = sum.variants.iter().map(|variant| variant.name.clone()).collect::<Vec<String>>().join("|");
+        format!("{}/** @typedef {{{}}} {} */\n", variants, names, sum.name)
+    }
+    fn declare_alias(&self, alias: &AliasType, mappings: &TypeMappings) -> String {
+        format!("/** @typedef {{{}}} {} */\n", self.render_type(&alias.target, mappings), alias.name)
+    }
+    fn render_function(&self, signature: &FunctionSignature, mappings: &TypeMappings) -> String {
+        let parameters = signature.input.iter().map(|param| self.render_parameter(param)).collect::<Vec<String>>().join(", ");
+        let output = if signature.is_async {
+            format!("Promise<{}>", self.render_type(&signature.output, mappings))
+        } else {
+            self.render_type(&signature.output, mappings)
+        };
+        let mut tags: Vec<String> = signature.type_parameters.iter().map(|type_parameter| format!("@template {}", type_parameter)).collect();
+        tags.extend(signature.input.iter().map(|param| self.jsdoc_tag(param, mappings)));
+        tags.push(format!("@returns {{{}}}", output));
+        format!("{}{}function {}({}) {{\n    throw new Error('not implemented');\n}}\n",
+            jsdoc(signature, &tags), signature.async_prefix(), signature.name, parameters)
+    }
+
+    // the typedefs are only read by editors and checkers, the export makes the file a module they can import from
+    fn domain_types_module(&self, definitions: &[String]) -> String {
+        let content = definitions.join("\n");
+        let imports = if mentions_type(&content, "Result") { format!("{}\n\n", import_result(Path::new("src"))) } else { String::new() };
+        format!("{}{}\nmodule.exports = {{}};\n", imports, content)
+    }
+    // the types live in JSDoc, so the file imports typedefs and exports its functions
+    fn application_module(&self, project: &Project, file: &ProcessedFile) -> String {
+        let functions = file.methods.iter().map(|method| self.render_function(&method.ast, project.mappings)).collect::<Vec<String>>().join("\n");
+
+        let from_dir = file.path.parent().unwrap_or(&file.path);
+        let mut imports: Vec<String> = vec![];
+        if !project.types.is_empty() {
+            imports.push(import_domain_types(from_dir, &project.type_names()));
+        }
+        imports.extend(support_imports(file));
+        imports.extend(file.list_type_modules().iter().map(|module_path| import_module(module_path)));
+        format!("{}\nmodule.exports = {{ {} }};\n", with_imports(&imports, functions), file.method_names().join(", "))
+    }
+    // a typedef per dependency, listing the methods the application calls on it
+    fn dependency_declarations(&self, dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+        dependencies.iter().map(|(dependency, methods)| {
+            let properties: String = methods.iter().map(|method| format!(" * @property {{function(...*): *}} {}\n", method)).collect();
+            format!("/**\n * @typedef {{Object}} {}\n{} */\n", dependency.trait_name(), properties)
+        }).collect()
+    }
+    fn mock_list(&self, configs: &[&MockConfig], _dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+        configs.iter().map(|config| config.mock.clone()).collect()
+    }
+
+    fn render_mock_test(&self, test: &MockTest, mock_refs: &[String], _mock_config: &MockConfig, target: &FunctionSignature) -> String {
+        jest_mock_test(test, mock_refs, target, "{}")
+    }
+    fn test_module(&self, _project: &Project, file: &ProcessedFile, methods_and_mocks: &[MethodAndMocks], is_main: bool) -> Option<TestFile> {
+        let mut tests: Vec<String> = methods_and_mocks.iter().flat_map(|method_and_mocks| mock_tests(self, method_and_mocks, is_main)).collect();
+        tests.extend(file.methods.iter().flat_map(|method| default_argument_test(self, method)));
+        tests.extend(file.methods.iter().flat_map(|method| literal_tests(self, method, Arguments::Positional)));
+
+        let test_file_path = test_path(file);
+        let test_dir = Path::new(&test_file_path).parent().unwrap_or_else(|| Path::new(""));
+        let mut imports = mock_imports(methods_and_mocks);
+        let mock_names = mock_names(file);
+        if !mock_names.is_empty() {
+            imports.push(import_functions(test_dir, Path::new("tests/mocks"), &mock_names));
+        }
+        let local_imports = vec![import_functions(test_dir, &file.path, &file.method_names())];
+        Some(TestFile::new(format!("{}.test.js", test_file_path), &imports, &local_imports, &tests))
+    }
+    fn main_test(&self, _project: &Project, _mock_names: &[String]) -> Option<TestFile> {
+        let handler_import = import_functions(Path::new("tests"), Path::new("src/index"), &[String::from("handler")]);
+        Some(TestFile::new(String::from("tests/index.test.js"), &[], &[handler_import], &[render_handler_test()]))
+    }
+    fn main_context(&self, _project: &Project, workflow: &Workflow) -> Context {
+        workflow.bound_context(import_workflow_functions, bind_dependencies)
+    }
+}
+
+impl CallTests for JavaScript {
+    fn test_argument(&self, param: &FunctionParameter, given: Option<String>) -> String {
+        jest_argument(param, given, "{}")
+    }
+    // jest describes the case in a sentence rather than naming it
+    fn call_test(&self, _test_name: &str, description: &str, target: &FunctionSignature, arguments: &str) -> String {
+        jest_call_test(description, target, arguments)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use crate::signature_parser::{ parse_signature, parse_type_definition };
+
+    #[test]
+    fn render_function_test() {
+        let mappings = TypeMappings::default();
+        let render = |signature: &str| JavaScript.render_function(&parse_signature(signature).unwrap(), &mappings);
+        assert_eq!(
+            render("async get_account(id: AccountId) -> Account"),
+            "/**\n * @param {AccountId} id\n * @returns {Promise<Account>}\n */\nasync function get_account(id) {\n    throw new Error('not implemented');\n}\n"
+        );
+        assert_eq!(
+            render("first<T>(items: List[T]) -> Optional[T]"),
+            "/**\n * @template T\n * @param {List<T>} items\n * @returns {Optional<T>}\n */\nfunction first(items) {\n    throw new Error('not implemented');\n}\n"
+        );
+        assert_eq!(
+            render("#: Fetches every user of the account\nget_users() -> Users"),
+            "/**\n * Fetches every user of the account\n * @returns {Users}\n */\nfunction get_users() {\n    throw new Error('not implemented');\n}\n"
+        );
+        assert_eq!(
+            render("count_users(limit: Int = 100, active: Bool = True) -> Users"),
+            "/**\n * @param {Int} [limit=100]\n * @param {Bool} [active=true]\n * @returns {Users}\n */\nfunction count_users(limit = 100, active = true) {\n    throw new Error('not implemented');\n}\n"
+        );
+    }
+
+    #[test]
+    fn render_type_test() {
+        let signature = parse_signature(
+            "f(a: User?, b: User | Admin | Guest, c: (AccountId, User), d: Literal['active', 'disabled'], e: boto3.session.Session, f: Int?) -> Result[List[User], String]"
+        ).unwrap();
+        let mappings = TypeMappings::standard();
+        let types: Vec<String> = signature.input.iter().map(|param| JavaScript.render_type(&param.ptype, &mappings)).collect();
+        assert_eq!(types, vec!["User | undefined", "User | Admin | Guest", "[AccountId, User]", "'active' | 'disabled'", "session.Session", "number | undefined"]);
+        assert_eq!(JavaScript.render_type(&signature.output, &mappings), "Result<User[], string>");
+    }
+
+    #[test]
+    fn declare_types_test() {
+        let declare = |definition: &str| JavaScript.render_type_definition(&parse_type_definition(definition).unwrap(), &TypeMappings::default());
+        assert_eq!(declare("User { id: UserId, email: Email }"), "/**\n * @typedef {Object} User\n * @property {UserId} id\n * @property {Email} email\n */\n");
+    }
+
+    #[test]
+    fn javascript_mock_test() {
+        let target = parse_signature("async get_account(os: {os}, id: AccountId, limit: Int = 100) -> Account").unwrap();
+        let test = MockTest { description: String::from("handle missing env var"), mock_response: String::from("mockReturnValue(undefined)") };
+        assert_eq!(
+            jest_mock_test(&test, &[String::from("OsMock.environ")], &target, "{}"),
+            "it('handle missing env var', async () => {\n    jest.spyOn(OsMock, 'environ').mockReturnValue(undefined);\n    const result = await get_account(OsMock, {});\n    expect(1).toBe(2);\n});"
+        )
+    }
+
+    #[test]
+    fn dependency_typedefs_test() {
+        let signature = "get_users(http_client: {requests: get}, ids: List[UserId]) -> Result[List[User], ErrorMsg]";
+        let method = ParsedMethod { raw: signature.to_string(), ast: parse_signature(signature).unwrap() };
+        let file = ProcessedFile::new(PathBuf::from("src/domains/users"), vec![method]);
+        assert_eq!(support_imports(&file), vec![
+            "/** @typedef {import('../dependencies').Requests} Requests */",
+            "/**\n * @template T, E\n * @typedef {import('../result').Result<T, E>} Result\n */",
+        ]);
+        let dependencies = file.list_dependencies().into_iter().map(|dep| (dep.clone(), dep.methods.clone())).collect::<Vec<_>>();
+        assert_eq!(
+            JavaScript.dependency_declarations(&dependencies),
+            vec!["/**\n * @typedef {Object} Requests\n * @property {function(...*): *} get\n */\n"]
+        );
+    }
+
+    #[test]
+    fn workflow_imports_test() {
+        let methods = ["get_users(http_client: {requests: get}) -> Users", "count(users: Users) -> Int"].iter()
+            .map(|signature| ParsedMethod { raw: signature.to_string(), ast: parse_signature(signature).unwrap() })
+            .collect::<Vec<ParsedMethod>>();
+        let file = ProcessedFile::new(PathBuf::from("src/domains/users"), methods.clone());
+        assert_eq!(import_workflow_functions(&file), "const { get_users: get_users_unbound, count } = require('./domains/users');");
+        assert_eq!(bind_dependencies(&methods[0]), "const get_users = get_users_unbound.bind(null, requests);\n");
+    }
+
+    #[test]
+    fn javascript_literal_tests_test() {
+        let signature = "set_level(client: {requests}, retries: Int = 3, level: Literal['active', 'disabled'] = 'active') -> None";
+        let method = ParsedMethod { raw: String::from(signature), ast: parse_signature(signature).unwrap() };
+        let tests = literal_tests(&JavaScript, &method, Arguments::Positional);
+        assert_eq!(tests[0], "it('accepts active as level', () => {\n    const result = set_level(RequestsMock, undefined, 'active');\n    expect(1).toBe(2);\n});");
+        assert_eq!(tests[2], "it('rejects an invalid level', () => {\n    const result = set_level(RequestsMock, undefined, '__invalid__');\n    expect(1).toBe(2);\n});");
+        assert_eq!(
+            default_argument_test(&JavaScript, &method).unwrap(),
+            "it('calls set_level with its default arguments', () => {\n    const result = set_level(RequestsMock);\n    expect(1).toBe(2);\n});"
+        );
+    }
+}
@@ -0,0 +1,436 @@
+use std::fmt;
+use std::path::{ Path, PathBuf };
+use failure::Fail;
+use tera::Context;
+
+use crate::domains::file::ProcessedFile;
+use crate::domains::function_signature::{ Dependency, FunctionParameter, FunctionSignature, LiteralType, ParameterType };
+use crate::domains::method::ParsedMethod;
+use crate::domains::schema::{ create_mock_name, MethodAndMocks, MockConfig, MockTest };
+use crate::domains::type_definition::{ AliasType, RecordType, SumType, TypeDefinition };
+use crate::domains::type_mapping::TypeMappings;
+
+pub mod go;
+pub mod javascript;
+pub mod python;
+pub mod rust;
+pub mod typescript;
+
+use self::go::Go;
+use self::javascript::JavaScript;
+use self::python::Python;
+use self::rust::Rust;
+use self::typescript::TypeScript;
+
+
+// Everything the generator needs to know about a target language: how it lays out a project and how
+// it writes types, functions and tests. The schema decides what gets generated, its backend how.
+pub trait Backend: fmt::Debug + Sync {
+    // the schema's name for the language, also the key of its type mappings
+    fn language(&self) -> &'static str;
+    fn file_extension(&self) -> &'static str;
+    // the directories under project_repository holding the language's templates and mock listing
+    fn template_set(&self) -> &'static str;
+    fn mock_listing_set(&self) -> &'static str;
+    // files rendered once per project besides the application and test files, as (template, path)
+    fn project_files(&self) -> &'static [(&'static str, &'static str)];
+
+    fn main_file(&self) -> &'static str;
+    fn domain_types_file(&self) -> String {
+        format!("src/domain_types.{}", self.file_extension())
+    }
+    fn dependencies_file(&self) -> String {
+        format!("src/dependencies.{}", self.file_extension())
+    }
+    fn mocks_file(&self) -> String {
+        format!("tests/mocks.{}", self.file_extension())
+    }
+    // the files which have to declare the module at `filename` before it can be imported, each with the
+    // line declaring it there, e.g. `src/domains/mod.rs` with `pub mod user;`
+    fn module_declarations(&self, filename: &Path) -> Vec<(PathBuf, String)>;
+
+    // built-in constructors are written the way the language's mappings say, everything else by the methods below
+    fn render_type(&self, ptype: &ParameterType, mappings: &TypeMappings) -> String {
+        let render_all = |types: &[ParameterType]| types.iter().map(|ptype| self.render_type(ptype, mappings)).collect::<Vec<String>>();
+        match ptype {
+            ParameterType::ApplicationType(value) => mapped_name(self.language(), &value.module_path, &value.type_name, &[], mappings)
+                .unwrap_or_else(|| self.qualified_type(&value.module_path, &value.type_name)),
+            ParameterType::ApplicationParentType(value) => {
+                let children = render_all(&value.children);
+                mapped_name(self.language(), &value.module_path, &value.type_name, &children, mappings)
+                    .unwrap_or_else(|| self.applied_type(&self.qualified_type(&value.module_path, &value.type_name), &children))
+            },
+            // `User?` is written the same way as the `Optional` constructor
+            ParameterType::OptionalType(value) => {
+                let inner = self.render_type(&value.inner, mappings);
+                mappings.render(self.language(), "Optional", std::slice::from_ref(&inner)).unwrap_or_else(|| self.optional_type(&inner))
+            },
+            ParameterType::UnionType(value) => self.union_type(&render_all(&value.members)),
+            ParameterType::TupleType(value) => self.tuple_type(&render_all(&value.members)),
+            ParameterType::Function(value) => self.function_type(&render_all(&value.params), &self.render_type(&value.output, mappings)),
+            ParameterType::LiteralType(value) => self.literal_type(value),
+            ParameterType::Dependency(value) => self.dependency_type(value),
+        }
+    }
+    // a type from another module, which is imported so the type is named through its last segment
+    fn qualified_type(&self, module_path: &[String], type_name: &str) -> String {
+        match module_path.last() {
+            Some(module) => format!("{}.{}", module, type_name),
+            None => type_name.to_string(),
+        }
+    }
+    // an unmapped constructor keeps its name and wraps the type arguments in the language's brackets
+    fn applied_type(&self, constructor: &str, arguments: &[String]) -> String {
+        format!("{}<{}>", constructor, arguments.join(", "))
+    }
+    // the optional type when the mappings have none
+    fn optional_type(&self, inner: &str) -> String;
+    fn union_type(&self, members: &[String]) -> String;
+    fn tuple_type(&self, members: &[String]) -> String;
+    fn function_type(&self, params: &[String], output: &str) -> String;
+    fn literal_type(&self, literal: &LiteralType) -> String;
+    fn dependency_type(&self, dependency: &Dependency) -> String;
+
+    fn render_type_definition(&self, definition: &TypeDefinition, mappings: &TypeMappings) -> String {
+        match definition {
+            TypeDefinition::Record(value) => self.declare_record(value, mappings),
+            TypeDefinition::Sum(value) => self.declare_sum(value, mappings),
+            TypeDefinition::Alias(value) => self.declare_alias(value, mappings),
+        }
+    }
+    fn declare_record(&self, record: &RecordType, mappings: &TypeMappings) -> String;
+    fn declare_sum(&self, sum: &SumType, mappings: &TypeMappings) -> String;
+    fn declare_alias(&self, alias: &AliasType, mappings: &TypeMappings) -> String;
+    fn render_function(&self, signature: &FunctionSignature, mappings: &TypeMappings) -> String;
+
+    // the module every declared type is written to, given their declarations in order
+    fn domain_types_module(&self, definitions: &[String]) -> String;
+    // an application file's functions with whatever they import
+    fn application_module(&self, project: &Project, file: &ProcessedFile) -> String;
+    // what the application takes each dependency as, naming the methods it calls on it
+    fn dependency_declarations(&self, dependencies: &[(Dependency, Vec<String>)]) -> Vec<String>;
+    // the listed mocks of the dependencies, with whatever else they need to stand in for them
+    fn mock_list(&self, configs: &[&MockConfig], dependencies: &[(Dependency, Vec<String>)]) -> Vec<String>;
+
+    // a fault scenario of one of the target's dependencies
+    fn render_mock_test(&self, test: &MockTest, mock_refs: &[String], mock_config: &MockConfig, target: &FunctionSignature) -> String;
+    // the tests of an application file, unless it has none to write
+    fn test_module(&self, project: &Project, file: &ProcessedFile, methods_and_mocks: &[MethodAndMocks], is_main: bool) -> Option<TestFile>;
+    // the test of the main file's handler, where the generated tests can reach it
+    fn main_test(&self, project: &Project, mock_names: &[String]) -> Option<TestFile>;
+    // what main.hbs needs to hand the workflow's steps their dependencies, besides the steps themselves
+    fn main_context(&self, project: &Project, workflow: &Workflow) -> Context;
+}
+
+
+// what a backend needs to know about the project besides the file it's writing
+#[derive(Debug)]
+pub struct Project<'a> {
+    pub name: &'a str,
+    pub root_directory: &'a str,
+    pub types: &'a [TypeDefinition],
+    pub mappings: &'a TypeMappings,
+}
+
+impl Project<'_> {
+    // the project name as it's written in code, e.g. `my_project` for my-project
+    pub fn crate_name(&self) -> String {
+        self.name.replace("-", "_")
+    }
+
+    pub fn type_names(&self) -> Vec<&str> {
+        self.types.iter().map(|definition| definition.name()).collect()
+    }
+
+    // whether the methods name any of the declared types
+    pub fn uses_declared_types<'a>(&self, methods: impl Iterator<Item = &'a ParsedMethod>) -> bool {
+        let declared = self.type_names();
+        methods.into_iter().any(|method| {
+            let mut used = false;
+            method.ast.walk_types(&mut |ptype| {
+                let type_name = match ptype {
+                    ParameterType::ApplicationType(value) => &value.type_name,
+                    ParameterType::ApplicationParentType(value) => &value.type_name,
+                    _ => return,
+                };
+                used |= declared.contains(&type_name.as_str());
+            });
+            used
+        })
+    }
+}
+
+// the steps the main file's handler runs, with the files and mocks of the project they come from
+#[derive(Debug)]
+pub struct Workflow<'a> {
+    pub files: &'a [ProcessedFile],
+    pub steps: Vec<(&'a ProcessedFile, &'a ParsedMethod)>,
+    pub mocks: &'a [MockConfig],
+}
+
+impl Workflow<'_> {
+    // the mock listing's statement constructing each of the dependencies, once per dependency
+    pub fn constructors<'a>(&self, dependencies: impl Iterator<Item = &'a Dependency>) -> Vec<String> {
+        let mut names: Vec<&str> = dependencies.map(|dep| dep.dependency_name.as_str()).collect();
+        names.sort();
+        names.dedup();
+        names.iter().flat_map(|name| self.mocks.iter().find(|mock| mock.name == *name))
+            .map(|config| config.import_statement.clone())
+            .collect()
+    }
+
+    // every file imported by the main file, every dependency constructed and each function given its dependencies
+    pub fn bound_context(&self, import: impl Fn(&ProcessedFile) -> String, bind: impl Fn(&ParsedMethod) -> String) -> Context {
+        let application_files: Vec<String> = self.files.iter().map(import).collect();
+        let functions_with_side_effects: Vec<String> = self.files.iter().map(|file| {
+            file.methods.iter().filter(|method| !method.list_dependencies().is_empty()).map(&bind).collect()
+        }).collect();
+        let mut context = Context::new();
+        context.insert("application_files", &application_files);
+        context.insert("dependencies", &self.constructors(self.files.iter().flat_map(|file| file.list_dependencies())));
+        context.insert("functions_with_side_effects", &functions_with_side_effects);
+        context
+    }
+}
+
+// a file rendered from test.hbs, and where it goes
+#[derive(Debug)]
+pub struct TestFile {
+    pub path: String,
+    pub context: Context,
+}
+
+impl TestFile {
+    pub fn new(path: String, imports: &[String], local_imports: &[String], tests: &[String]) -> TestFile {
+        let mut context = Context::new();
+        context.insert("imports", imports);
+        context.insert("local_imports", local_imports);
+        context.insert("tests", tests);
+        TestFile { path, context }
+    }
+}
+
+
+// the backends whose tests call the target once per case, rather than from a table of cases
+pub trait CallTests {
+    // the argument a case passes for the parameter, the value given to it or a stand-in
+    fn test_argument(&self, param: &FunctionParameter, given: Option<String>) -> String;
+    fn call_test(&self, test_name: &str, description: &str, target: &FunctionSignature, arguments: &str) -> String;
+}
+
+// which of the target's parameters a case passes
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Arguments {
+    // by name, so only those without a default and the one the case is about
+    Named,
+    // by position, so the defaulted ones before the one the case is about too
+    Positional,
+    // all of them, the language has no default arguments
+    Every,
+}
+
+// built-in constructors are written the way the language's mapping says, qualified types never are
+fn mapped_name(language: &str, module_path: &[String], type_name: &str, children: &[String], mappings: &TypeMappings) -> Option<String> {
+    if module_path.is_empty() {
+        mappings.render(language, type_name, children)
+    } else {
+        None
+    }
+}
+
+// each fault scenario of each mocked dependency, patching the methods the target calls on it
+pub fn mock_tests<B: Backend + ?Sized>(backend: &B, method_and_mocks: &MethodAndMocks, is_main: bool) -> Vec<String> {
+    method_and_mocks.mocks.iter().flat_map(|config| {
+        // patch the methods the signature says it calls, falling back to the mock's own list
+        let dependency = method_and_mocks.method.list_dependencies().into_iter().find(|dep| dep.dependency_name == config.name);
+        let patched_methods = match dependency {
+            Some(dep) if !dep.methods.is_empty() => &dep.methods,
+            _ => &config.methods,
+        };
+        let mock_refs: Vec<String> = patched_methods.iter().map(|patched_method| {
+            if is_main {
+                format!("main.{}.{}", config.name, patched_method)
+            } else {
+                format!("{}.{}", create_mock_name(config.name.as_str()), patched_method)
+            }
+        }).collect();
+        let target = &method_and_mocks.method.ast;
+        config.tests.iter().map(|test| backend.render_mock_test(test, &mock_refs, config, target)).collect::<Vec<String>>()
+    }).collect()
+}
+
+// mocks for dependencies and placeholders for everything else, unless a value is given for the parameter
+fn test_call_arguments<B: CallTests + ?Sized>(backend: &B, params: &[&FunctionParameter], values: &[(&str, String)]) -> String {
+    params.iter().map(|param| {
+        let given = values.iter().find(|(name, _)| *name == param.name).map(|(_, value)| value.clone());
+        backend.test_argument(param, given)
+    }).collect::<Vec<String>>().join(", ")
+}
+
+// a case which leaves every defaulted argument out of the call
+pub fn default_argument_test<B: CallTests + ?Sized>(backend: &B, method: &ParsedMethod) -> Option<String> {
+    if !method.ast.has_default_arguments() {
+        return None;
+    }
+
+    let method_name = method.ast.name.as_str();
+    let params: Vec<&FunctionParameter> = method.ast.input.iter().filter(|param| param.default.is_none()).collect();
+    let arguments = test_call_arguments(backend, &params, &[]);
+    Some(backend.call_test(
+        &format!("test_{}_with_default_arguments", method_name),
+        &format!("calls {} with its default arguments", method_name),
+        &method.ast,
+        &arguments,
+    ))
+}
+
+// one case per allowed value of each literal-typed parameter, plus one with a value outside the set
+pub fn literal_tests<B: CallTests + ?Sized>(backend: &B, method: &ParsedMethod, passed: Arguments) -> Vec<String> {
+    let method_name = method.ast.name.as_str();
+    method.ast.input.iter().enumerate().flat_map(|(literal_index, literal_param)| {
+        let literal = match &literal_param.ptype {
+            ParameterType::LiteralType(literal) => literal,
+            _ => return vec![],
+        };
+        let params: Vec<&FunctionParameter> = method.ast.input.iter().enumerate()
+            .filter(|(index, param)| match passed {
+                Arguments::Named => param.default.is_none() || *index == literal_index,
+                Arguments::Positional => param.default.is_none() || *index <= literal_index,
+                Arguments::Every => true,
+            })
+            .map(|(_, param)| param)
+            .collect();
+        let param_name = literal_param.name.as_str();
+
+        let value_names: Vec<String> = literal.values.iter()
+            .map(|value| value.chars().filter(|c| c.is_alphanumeric() || *c == '_' || *c == ' ').collect::<String>().replace(" ", "_").to_lowercase())
+            .collect();
+        let mut tests: Vec<String> = literal.values.iter().zip(value_names.iter()).enumerate().map(|(index, (value, value_name))| {
+            let arguments = test_call_arguments(backend, &params, &[(param_name, value.clone())]);
+            // values differing only in case or sign would share a name, so those are told apart by position
+            let test_name = if value_names.iter().filter(|name| *name == value_name).count() > 1 {
+                format!("test_{}_accepts_{}_{}_{}", method_name, param_name, value_name, index)
+            } else {
+                format!("test_{}_accepts_{}_{}", method_name, param_name, value_name)
+            };
+            let description = format!("accepts {} as {}", value.replace(['\'', '"'], ""), param_name);
+            backend.call_test(&test_name, &description, &method.ast, &arguments)
+        }).collect();
+
+        let arguments = test_call_arguments(backend, &params, &[(param_name, literal.invalid_value())]);
+        tests.push(backend.call_test(
+            &format!("test_{}_rejects_invalid_{}", method_name, param_name),
+            &format!("rejects an invalid {}", param_name),
+            &method.ast,
+            &arguments,
+        ));
+        tests
+    }).collect()
+}
+
+// what the mock listing says the mocks of a file's methods import
+pub fn mock_imports(methods_and_mocks: &[MethodAndMocks]) -> Vec<String> {
+    methods_and_mocks.iter().map(|mams| {
+        mams.mocks.iter().flat_map(|mock| mock.imports.clone()).collect()
+    }).filter(|import: &String| !import.is_empty()).collect()
+}
+
+// the mocks a file's tests hand its methods, once each
+pub fn mock_names(file: &ProcessedFile) -> Vec<String> {
+    let mut mock_names: Vec<String> = file.list_dependencies().iter().map(|dep| create_mock_name(&dep.dependency_name)).collect();
+    mock_names.sort();
+    mock_names.dedup();
+    mock_names
+}
+
+// a module's imports above its content
+pub fn with_imports(imports: &[String], content: String) -> String {
+    if imports.is_empty() {
+        content
+    } else {
+        format!("{}\n\n\n{}", imports.join("\n"), content)
+    }
+}
+
+// whether rendered code names the type, e.g. a declaration using `Result` has to import it
+pub fn mentions_type(content: &str, type_name: &str) -> bool {
+    content.split(|c: char| !c.is_alphanumeric() && c != '_').any(|word| word == type_name)
+}
+
+// a test file mirrors its application file under tests/
+pub fn test_path(file: &ProcessedFile) -> String {
+    file.path.as_path().display().to_string().replace("src", "tests")
+}
+
+
+static BACKENDS: [&dyn Backend; 5] = [&Python, &JavaScript, &TypeScript, &Rust, &Go];
+
+pub fn find_backend(language: &str) -> Result<&'static dyn Backend, UnsupportedLanguage> {
+    BACKENDS.iter().copied()
+        .find(|backend| backend.language() == language)
+        .ok_or_else(|| UnsupportedLanguage { language: language.to_string() })
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct UnsupportedLanguage {
+    pub language: String,
+}
+
+impl fmt::Display for UnsupportedLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let supported: Vec<&str> = BACKENDS.iter().map(|backend| backend.language()).collect();
+        write!(f, "unsupported language '{}', expected one of: {}", self.language, supported.join(", "))
+    }
+}
+
+impl Fail for UnsupportedLanguage {}
+
+
+// a `require` path from the module in `from_dir` to `target`, e.g. `../domain_types`
+pub fn relative_module_path(from_dir: &Path, target: &Path) -> String {
+    let from: Vec<_> = from_dir.components().collect();
+    let to: Vec<_> = target.components().collect();
+    let shared = from.iter().zip(to.iter()).take_while(|(a, b)| a == b).count();
+    let mut parts: Vec<String> = vec![String::from(".."); from.len() - shared];
+    parts.extend(to[shared..].iter().map(|component| component.as_os_str().to_string_lossy().to_string()));
+    if from.len() == shared {
+        format!("./{}", parts.join("/"))
+    } else {
+        parts.join("/")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature_parser::parse_signature;
+
+    #[test]
+    fn find_backend_test() {
+        assert_eq!(find_backend("typescript").unwrap().file_extension(), "ts");
+        assert_eq!(
+            find_backend("cobol").unwrap_err().to_string(),
+            "unsupported language 'cobol', expected one of: python, javascript, typescript, rust, go"
+        );
+    }
+
+    #[test]
+    fn literal_tests_test() {
+        let signature = "set_level(client: {requests}, retries: Int = 3, level: Literal['active', 'disabled'] = 'active', limit: Int = 10) -> None";
+        let method = ParsedMethod { raw: String::from(signature), ast: parse_signature(signature).unwrap() };
+        let calls = |passed: Arguments| literal_tests(&Python, &method, passed).iter()
+            .map(|test| test.lines().nth(1).unwrap().trim().to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(calls(Arguments::Named)[0], "result = set_level(client=RequestsMock, level='active')");
+        assert_eq!(calls(Arguments::Positional)[0], "result = set_level(client=RequestsMock, retries=MagicMock(), level='active')");
+        assert_eq!(calls(Arguments::Every)[2], "result = set_level(client=RequestsMock, retries=MagicMock(), level='__invalid__', limit=MagicMock())");
+    }
+
+    #[test]
+    fn relative_module_path_test() {
+        assert_eq!(relative_module_path(Path::new("src/domains"), Path::new("src/domain_types")), "../domain_types");
+        assert_eq!(relative_module_path(Path::new("src"), Path::new("src/domains/account")), "./domains/account");
+        assert_eq!(relative_module_path(Path::new("tests/domains"), Path::new("src/domains/account")), "../../src/domains/account");
+    }
+}
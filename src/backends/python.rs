@@ -0,0 +1,506 @@
+use std::path::{ Path, PathBuf };
+use tera::Context;
+
+use crate::backends::{
+    default_argument_test, literal_tests, mentions_type, mock_imports, mock_names, mock_tests, test_path, with_imports,
+    Arguments, Backend, CallTests, Project, TestFile, Workflow
+};
+use crate::domains::file::ProcessedFile;
+use crate::domains::function_signature::{ Dependency, FunctionParameter, FunctionSignature, LiteralType, ParameterType };
+use crate::domains::schema::{ create_mock_name, MethodAndMocks, MockConfig, MockTest };
+use crate::domains::type_definition::{ AliasType, RecordType, SumType };
+use crate::domains::type_mapping::TypeMappings;
+
+
+#[derive(Debug)]
+pub struct Python;
+
+const TYPING_NAMES: &[&str] = &["Any", "Callable", "Dict", "List", "Literal", "Optional", "Set", "Tuple", "TypeVar", "Union"];
+
+// `from typing import List, Optional` for whichever typing names the rendered python uses
+fn typing_import(content: &str) -> Option<String> {
+    let words: Vec<&str> = content.split(|c: char| !c.is_alphanumeric() && c != '_').collect();
+    let used: Vec<&str> = TYPING_NAMES.iter().copied().filter(|name| words.contains(name)).collect();
+    if used.is_empty() {
+        None
+    } else {
+        Some(format!("from typing import {}", used.join(", ")))
+    }
+}
+
+fn get_dot_separated_path(path: &Path) -> String {
+    let path_string = path.display().to_string();
+    path_string.split("/").collect::<Vec<&str>>().join(".")
+}
+
+// the tests import the application the way the main file does, from the directory above
+fn sys_path_assignment(path: &str) -> String {
+    format!(r#"BASE_DIR = os.path.dirname(os.path.abspath(__file__))
+sys.path.append(BASE_DIR)
+sys.path.insert(0, os.path.join(BASE_DIR, "../{}"))"#, path)
+}
+
+// async targets get a pytest-asyncio test which awaits the call
+fn python_test_definition(test_name: &str, target: &FunctionSignature) -> (String, &'static str) {
+    if target.is_async {
+        (format!("@pytest.mark.asyncio\nasync def {}():", test_name), "await ")
+    } else {
+        (format!("def {}():", test_name), "")
+    }
+}
+
+// backslashes and quotes which would end the docstring early are escaped, continuation lines are
+// indented with the body and the closing quotes go on a line of their own
+fn python_docstring(description: &str) -> String {
+    let escaped = description.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+    let escaped = match escaped.strip_suffix('"') {
+        Some(rest) if rest.chars().rev().take_while(|c| *c == '\\').count() % 2 == 0 => format!("{}\\\"", rest),
+        _ => escaped,
+    };
+    let mut lines = escaped.lines();
+    let first = lines.next().unwrap_or("");
+    let rest: String = lines.map(|line| if line.trim().is_empty() { String::from("\n") } else { format!("    {}\n", line) }).collect();
+    if rest.is_empty() {
+        format!("    \"\"\"{}\"\"\"\n", first)
+    } else {
+        format!("    \"\"\"{}\n{}    \"\"\"\n", first, rest)
+    }
+}
+
+impl Python {
+    fn render_parameter(&self, param: &FunctionParameter, mappings: &TypeMappings) -> String {
+        let default = match &param.default {
+            Some(default) => format!(" = {}", default),
+            None => String::new(),
+        };
+        format!("{}: {}{}", param.name, self.render_type(&param.ptype, mappings), default)
+    }
+}
+
+impl Backend for Python {
+    fn language(&self) -> &'static str {
+        "python"
+    }
+    fn file_extension(&self) -> &'static str {
+        "py"
+    }
+    fn template_set(&self) -> &'static str {
+        "python38"
+    }
+    fn mock_listing_set(&self) -> &'static str {
+        "python"
+    }
+    fn project_files(&self) -> &'static [(&'static str, &'static str)] {
+        &[("result.hbs", "src/result.py")]
+    }
+    fn main_file(&self) -> &'static str {
+        "src/main.py"
+    }
+    // every directory holding a module is a package
+    fn module_declarations(&self, filename: &Path) -> Vec<(PathBuf, String)> {
+        let directory = filename.parent().unwrap_or_else(|| Path::new(""));
+        vec![(directory.join("__init__.py"), String::new())]
+    }
+
+    // python refers to a qualified type by its full dotted path
+    fn qualified_type(&self, module_path: &[String], type_name: &str) -> String {
+        if module_path.is_empty() {
+            type_name.to_string()
+        } else {
+            format!("{}.{}", module_path.join("."), type_name)
+        }
+    }
+    fn applied_type(&self, constructor: &str, arguments: &[String]) -> String {
+        format!("{}[{}]", constructor, arguments.join(", "))
+    }
+    fn optional_type(&self, inner: &str) -> String {
+        format!("Optional[{}]", inner)
+    }
+    fn union_type(&self, members: &[String]) -> String {
+        format!("Union[{}]", members.join(", "))
+    }
+    fn tuple_type(&self, members: &[String]) -> String {
+        if members.is_empty() {
+            String::from("Tuple[()]")
+        } else {
+            format!("Tuple[{}]", members.join(", "))
+        }
+    }
+    fn function_type(&self, params: &[String], output: &str) -> String {
+        format!("Callable[[{}], {}]", params.join(", "), output)
+    }
+    fn literal_type(&self, literal: &LiteralType) -> String {
+        format!("Literal[{}]", literal.values.join(", "))
+    }
+    // the protocol declared in the generated dependencies module
+    fn dependency_type(&self, dependency: &Dependency) -> String {
+        dependency.trait_name()
+    }
+
+    fn declare_record(&self, record: &RecordType, mappings: &TypeMappings) -> String {
+        let fields = if record.fields.is_empty() {
+            String::from("    pass\n")
+        } else {
+            record.fields.iter().map(|field| format!("    {}: {}\n", field.name, self.render_type(&field.ftype, mappings))).collect()
+        };
+        format!("@dataclass\nclass {}:\n{}", record.name, fields)
+    }
+    fn declare_sum(&self, sum: &SumType, mappings: &TypeMappings) -> String {
+        let variants = sum.variants.iter().map(|variant| self.declare_record(variant, mappings)).collect::<Vec<String>>().join("\n\n");
+        let names = sum.variants.iter().map(|variant| variant.name.clone()).collect::<Vec<String>>().join(", ");
+        format!("{}\n\n{} = Union[{}]\n", variants, sum.name, names)
+    }
+    fn declare_alias(&self, alias: &AliasType, mappings: &TypeMappings) -> String {
+        format!("{} = {}\n", alias.name, self.render_type(&alias.target, mappings))
+    }
+    fn render_function(&self, signature: &FunctionSignature, mappings: &TypeMappings) -> String {
+        let parameters = signature.input.iter().map(|param| self.render_parameter(param, mappings)).collect::<Vec<String>>().join(", ");
+        let docstring = match &signature.description {
+            Some(description) => python_docstring(description),
+            None => String::new(),
+        };
+        format!("{}def {}({}) -> {}:\n{}    pass\n",
+            signature.async_prefix(), signature.name, parameters, self.render_type(&signature.output, mappings), docstring)
+    }
+
+    fn domain_types_module(&self, definitions: &[String]) -> String {
+        let content = definitions.join("\n\n");
+        // postponed annotations let a dataclass refer to types declared further down
+        let mut imports: Vec<String> = vec![String::from("from __future__ import annotations"), String::from("from dataclasses import dataclass")]
+            .into_iter().chain(typing_import(&content)).collect();
+        if mentions_type(&content, "Result") {
+            imports.push(String::from("from src.result import Result"));
+        }
+        with_imports(&imports, content)
+    }
+    fn application_module(&self, project: &Project, file: &ProcessedFile) -> String {
+        let functions = file.methods.iter().map(|method| self.render_function(&method.ast, project.mappings)).collect::<Vec<String>>().join("\n");
+
+        // python declares each type parameter once per module, however many functions share it
+        let type_parameters = file.list_type_parameters();
+        let functions = if type_parameters.is_empty() {
+            functions
+        } else {
+            let declarations = type_parameters.iter().map(|type_parameter| format!("{} = TypeVar('{}')", type_parameter, type_parameter)).collect::<Vec<String>>();
+            format!("{}\n\n\n{}", declarations.join("\n"), functions)
+        };
+
+        let mut imports: Vec<String> = typing_import(&functions).into_iter().collect();
+        if !project.types.is_empty() {
+            imports.push(String::from("from src.domain_types import *"));
+        }
+        let protocols = file.list_dependency_types();
+        if !protocols.is_empty() {
+            imports.push(format!("from src.dependencies import {}", protocols.join(", ")));
+        }
+        if file.methods.iter().any(|method| method.ast.uses_type("Result")) {
+            imports.push(String::from("from src.result import Result"));
+        }
+        imports.extend(file.list_type_modules().iter().map(|module_path| format!("import {}", module_path.join("."))));
+        with_imports(&imports, functions)
+    }
+    // a protocol per dependency, naming the methods the application calls on it
+    fn dependency_declarations(&self, dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+        dependencies.iter().map(|(dependency, methods)| {
+            let declarations: String = if methods.is_empty() {
+                String::from("    pass\n")
+            } else {
+                methods.iter().map(|method| format!("    def {}(self, *args: Any, **kwargs: Any) -> Any: ...\n", method)).collect()
+            };
+            format!("class {}(Protocol):\n{}", dependency.trait_name(), declarations)
+        }).collect()
+    }
+    fn mock_list(&self, configs: &[&MockConfig], _dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+        configs.iter().map(|config| config.mock.clone()).collect()
+    }
+
+    fn render_mock_test(&self, test: &MockTest, mock_refs: &[String], mock_config: &MockConfig, target: &FunctionSignature) -> String {
+        let snake_case_description = test.description.replace(" ", "_");
+        let (definition, call_prefix) = python_test_definition(&format!("test_{}", snake_case_description), target);
+        let mock_class = if target.is_async { "AsyncMock" } else { "MagicMock" };
+        let patches: String = mock_refs.iter()
+            .map(|mock_ref| format!("\n    {} = {}({})", mock_ref, mock_class, test.mock_response))
+            .collect();
+        format!("{}{}\n    result = {}{}({})\n    assert 1 == 2",
+            definition, patches, call_prefix, target.name, create_mock_name(&mock_config.name))
+    }
+    fn test_module(&self, _project: &Project, file: &ProcessedFile, methods_and_mocks: &[MethodAndMocks], is_main: bool) -> Option<TestFile> {
+        let mut tests: Vec<String> = methods_and_mocks.iter().flat_map(|method_and_mocks| mock_tests(self, method_and_mocks, is_main)).collect();
+        tests.extend(file.methods.iter().flat_map(|method| default_argument_test(self, method)));
+        tests.extend(file.methods.iter().flat_map(|method| literal_tests(self, method, Arguments::Named)));
+
+        let mut imports = mock_imports(methods_and_mocks);
+        // TODO: it should import RequestsMock not requests?
+        let mock_names = mock_names(file);
+        if !mock_names.is_empty() {
+            imports.push(format!("from tests.mocks import {}", mock_names.join(", ")));
+        }
+        if file.methods.iter().any(|method| method.ast.is_async) {
+            imports.push(String::from("import pytest\nfrom unittest.mock import AsyncMock"));
+        }
+        // from src.domains.user import User, PersistedUser  # noqa
+        let local_imports = vec![format!("from {} import {}", get_dot_separated_path(&file.path), file.method_names().join(", "))];
+
+        let mut test_file = TestFile::new(format!("{}.py", test_path(file)), &imports, &local_imports, &tests);
+        test_file.context.insert("sys_path_assignment", &sys_path_assignment(&file.path.display().to_string()));
+        Some(test_file)
+    }
+    // a test_main.py which has every mock to hand
+    fn main_test(&self, project: &Project, mock_names: &[String]) -> Option<TestFile> {
+        let imports: Vec<String> = if mock_names.is_empty() {
+            vec![]
+        } else {
+            vec![format!("from tests.mocks import {}", mock_names.join(", "))]
+        };
+        let mut test_file = TestFile::new(String::from("tests/test_main.py"), &imports, &[String::from("import main")], &[]);
+        test_file.context.insert("sys_path_assignment", &sys_path_assignment(project.root_directory));
+        Some(test_file)
+    }
+    // functions with dependencies are partially applied to them
+    fn main_context(&self, _project: &Project, workflow: &Workflow) -> Context {
+        workflow.bound_context(
+            |file| format!("from {} import {}", get_dot_separated_path(&file.path), file.method_names().join(", ")),
+            |method| {
+                let dependency_names: Vec<String> = method.list_dependencies().iter().map(|dep| dep.dependency_name.clone()).collect();
+                format!("{} = partial({}, {})\n", method.ast.name, method.ast.name, dependency_names.join(", "))
+            },
+        )
+    }
+}
+
+// arguments are passed by name, a stand-in is only needed for those without a default
+impl CallTests for Python {
+    fn test_argument(&self, param: &FunctionParameter, given: Option<String>) -> String {
+        let value = match (given, &param.ptype) {
+            (Some(value), _) => value,
+            (None, ParameterType::Dependency(dep)) => create_mock_name(&dep.dependency_name),
+            _ => String::from("MagicMock()"),
+        };
+        format!("{}={}", param.name, value)
+    }
+    fn call_test(&self, test_name: &str, _description: &str, target: &FunctionSignature, arguments: &str) -> String {
+        let (definition, call_prefix) = python_test_definition(test_name, target);
+        format!("{}\n    result = {}{}({})\n    assert 1 == 2", definition, call_prefix, target.name, arguments)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::function_signature::{ ApplicationParentType, ApplicationType, FunctionType, OptionalType, TupleType, UnionType };
+    use crate::domains::method::ParsedMethod;
+    use crate::domains::type_definition::{ TypeDefinition, TypeField };
+    use crate::signature_parser::parse_signature;
+
+    fn field(name: &str, type_name: &str) -> TypeField {
+        TypeField {
+            name: String::from(name),
+            ftype: ParameterType::ApplicationType(ApplicationType { type_name: String::from(type_name), module_path: vec![] }),
+        }
+    }
+
+    #[test]
+    fn render_function_test() {
+        let ast = FunctionSignature {
+            name: String::from("get_users_for_account"),
+            input: vec![
+                FunctionParameter {
+                    name: String::from("http_client"),
+                    ptype: ParameterType::Dependency(
+                        Dependency { dependency_name: String::from("requests"), methods: vec![] }
+                    ),
+                    default: None
+                },
+                FunctionParameter {
+                    name: String::from("account_ids"),
+                    ptype: ParameterType::ApplicationParentType(
+                        ApplicationParentType {
+                            type_name: String::from("List"),
+                            children: vec![
+                                ParameterType::ApplicationType(
+                                    ApplicationType { type_name: String::from("AccountId"), module_path: vec![] }
+                                )
+                            ],
+                            module_path: vec![]
+                        }
+                    ),
+                    default: None
+                }
+            ],
+            output: ParameterType::ApplicationParentType(
+                ApplicationParentType {
+                    type_name: String::from("Result"),
+                    children: vec![
+                        ParameterType::ApplicationParentType(
+                            ApplicationParentType {
+                                type_name: String::from("List"),
+                                children: vec![
+                                    ParameterType::ApplicationType(
+                                        ApplicationType { type_name: String::from("User"), module_path: vec![] }
+                                    )
+                                ],
+                                module_path: vec![]
+                            }
+                        ),
+                        ParameterType::ApplicationType(
+                            ApplicationType { type_name: String::from("ErrorMsg"), module_path: vec![] }
+                        )
+                    ],
+                    module_path: vec![]
+                }
+            ),
+            is_async: false,
+            type_parameters: vec![],
+            description: None
+        };
+        let result = Python.render_function(&ast, &TypeMappings::default());
+        let expected = r#"def get_users_for_account(http_client: Requests, account_ids: List[AccountId]) -> Result[List[User], ErrorMsg]:
+    pass
+"#;
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn optional_type_test() {
+        let ptype = ParameterType::OptionalType(
+            OptionalType {
+                inner: Box::new(ParameterType::ApplicationType(
+                    ApplicationType { type_name: String::from("User"), module_path: vec![] }
+                ))
+            }
+        );
+        assert_eq!(Python.render_type(&ptype, &TypeMappings::default()), "Optional[User]");
+    }
+
+    #[test]
+    fn union_and_tuple_type_test() {
+        let application_type = |name: &str| ParameterType::ApplicationType(ApplicationType { type_name: String::from(name), module_path: vec![] });
+        let union = ParameterType::UnionType(
+            UnionType { members: vec![application_type("User"), application_type("Admin"), application_type("Guest")] }
+        );
+        assert_eq!(Python.render_type(&union, &TypeMappings::default()), "Union[User, Admin, Guest]");
+
+        let tuple = ParameterType::TupleType(
+            TupleType { members: vec![application_type("AccountId"), application_type("User")] }
+        );
+        assert_eq!(Python.render_type(&tuple, &TypeMappings::default()), "Tuple[AccountId, User]");
+    }
+
+    #[test]
+    fn function_type_test() {
+        let application_type = |name: &str| ParameterType::ApplicationType(ApplicationType { type_name: String::from(name), module_path: vec![] });
+        let function = ParameterType::Function(
+            FunctionType {
+                params: vec![application_type("AccountId")],
+                output: Box::new(ParameterType::ApplicationParentType(
+                    ApplicationParentType {
+                        type_name: String::from("Result"),
+                        children: vec![application_type("User"), application_type("ErrorMsg")],
+                        module_path: vec![]
+                    }
+                ))
+            }
+        );
+        assert_eq!(Python.render_type(&function, &TypeMappings::default()), "Callable[[AccountId], Result[User, ErrorMsg]]");
+    }
+
+    #[test]
+    fn default_parameter_test() {
+        let ast = parse_signature("count_users(limit: Int = 100) -> Users").unwrap();
+        assert_eq!(Python.render_function(&ast, &TypeMappings::default()), "def count_users(limit: Int = 100) -> Users:\n    pass\n");
+    }
+
+    #[test]
+    fn async_function_test() {
+        let ast = parse_signature("async get_account(id: AccountId) -> Account").unwrap();
+        assert_eq!(Python.render_function(&ast, &TypeMappings::default()), "async def get_account(id: AccountId) -> Account:\n    pass\n");
+    }
+
+    #[test]
+    fn generic_function_test() {
+        let ast = parse_signature("first<T>(items: List[T]) -> Optional[T]").unwrap();
+        // the TypeVar is declared once by the module, not by each function using it
+        assert_eq!(Python.render_function(&ast, &TypeMappings::default()), "def first(items: List[T]) -> Optional[T]:\n    pass\n");
+    }
+
+    #[test]
+    fn description_test() {
+        let ast = parse_signature("#: Fetches every user of the account\nget_users() -> Users").unwrap();
+        assert_eq!(Python.render_function(&ast, &TypeMappings::default()), "def get_users() -> Users:\n    \"\"\"Fetches every user of the account\"\"\"\n    pass\n");
+    }
+
+    #[test]
+    fn python_docstring_test() {
+        assert_eq!(python_docstring(r#"Reads C:\data and says "hi""#), "    \"\"\"Reads C:\\\\data and says \"hi\\\"\"\"\"\n");
+        assert_eq!(python_docstring(r#"Quotes """ inside"#), "    \"\"\"Quotes \\\"\\\"\\\" inside\"\"\"\n");
+        assert_eq!(python_docstring("Fetches the users\n\nSkips disabled ones"), "    \"\"\"Fetches the users\n\n    Skips disabled ones\n    \"\"\"\n");
+    }
+
+    #[test]
+    fn literal_type_test() {
+        let ptype = ParameterType::LiteralType(
+            LiteralType { values: vec![String::from("'active'"), String::from("'disabled'")] }
+        );
+        assert_eq!(Python.render_type(&ptype, &TypeMappings::default()), "Literal['active', 'disabled']");
+    }
+
+    #[test]
+    fn qualified_type_test() {
+        let ptype = ParameterType::ApplicationType(
+            ApplicationType { type_name: String::from("Session"), module_path: vec![String::from("boto3"), String::from("session")] }
+        );
+        assert_eq!(Python.render_type(&ptype, &TypeMappings::default()), "boto3.session.Session");
+    }
+
+    #[test]
+    fn mapped_type_test() {
+        let signature = parse_signature(
+            "get_users(http_client: {requests}, ids: List[UserId], limit: Int?) -> Result[List[User], String]"
+        ).unwrap();
+        let mappings = TypeMappings::standard();
+        assert_eq!(Python.render_function(&signature, &mappings), "def get_users(http_client: Requests, ids: List[UserId], limit: Optional[int]) -> Result[List[User], str]:\n    pass\n");
+    }
+
+    #[test]
+    fn declare_types_test() {
+        let record = TypeDefinition::Record(RecordType {
+            name: String::from("User"),
+            fields: vec![field("id", "UserId"), field("email", "Email")],
+        });
+        assert_eq!(Python.render_type_definition(&record, &TypeMappings::default()), "@dataclass\nclass User:\n    id: UserId\n    email: Email\n");
+
+        let sum = TypeDefinition::Sum(SumType {
+            name: String::from("Status"),
+            variants: vec![
+                RecordType { name: String::from("Active"), fields: vec![] },
+                RecordType { name: String::from("Disabled"), fields: vec![field("reason", "String")] },
+            ],
+        });
+        assert_eq!(Python.render_type_definition(&sum, &TypeMappings::default()), "@dataclass\nclass Active:\n    pass\n\n\n@dataclass\nclass Disabled:\n    reason: String\n\n\nStatus = Union[Active, Disabled]\n");
+    }
+
+    #[test]
+    fn literal_tests_test() {
+        let signature = "set_level(level: Literal['Active', 'active', -1, 1]) -> None";
+        let method = ParsedMethod { raw: String::from(signature), ast: parse_signature(signature).unwrap() };
+        let tests = literal_tests(&Python, &method, Arguments::Named);
+        let names: Vec<&str> = tests.iter().map(|test| test.lines().next().unwrap()).collect();
+        assert_eq!(names, vec![
+            "def test_set_level_accepts_level_active_0():",
+            "def test_set_level_accepts_level_active_1():",
+            "def test_set_level_accepts_level_1_2():",
+            "def test_set_level_accepts_level_1_3():",
+            "def test_set_level_rejects_invalid_level():",
+        ]);
+        assert_eq!(tests[0], "def test_set_level_accepts_level_active_0():\n    result = set_level(level='Active')\n    assert 1 == 2");
+        assert_eq!(tests[4], "def test_set_level_rejects_invalid_level():\n    result = set_level(level='__invalid__')\n    assert 1 == 2");
+    }
+
+    #[test]
+    fn typing_import_test() {
+        let content = "Principal = Union[User, Admin]\n\n@dataclass\nclass Filter:\n    status: Literal['active']\n    keys: Tuple[int, str]\n";
+        assert_eq!(typing_import(content), Some(String::from("from typing import Literal, Tuple, Union")));
+        assert_eq!(typing_import("@dataclass\nclass User:\n    id: UserId\n"), None);
+    }
+}
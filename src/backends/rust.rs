@@ -0,0 +1,462 @@
+use std::path::{ Path, PathBuf };
+use tera::Context;
+
+use crate::backends::{ literal_tests, mock_imports, mock_names, mock_tests, with_imports, Arguments, Backend, CallTests, Project, TestFile, Workflow };
+use crate::domains::file::ProcessedFile;
+use crate::domains::function_signature::{ Dependency, FunctionParameter, FunctionSignature, LiteralType, ParameterType };
+use crate::domains::method::ParsedMethod;
+use crate::domains::schema::{ create_mock_name, MethodAndMocks, MockConfig, MockTest };
+use crate::domains::type_definition::{ AliasType, RecordType, SumType };
+use crate::domains::type_mapping::TypeMappings;
+
+
+#[derive(Debug)]
+pub struct Rust;
+
+// schema defaults are written the Python way, e.g. `'active'` becomes `"active"`
+fn rust_value(value: &str, ptype: &ParameterType) -> String {
+    let is_string = matches!(ptype, ParameterType::ApplicationType(value) if value.type_name == "String" && value.module_path.is_empty());
+    match value {
+        "None" => String::from("None"),
+        "True" => String::from("true"),
+        "False" => String::from("false"),
+        quoted if quoted.len() > 1 && (quoted.starts_with('\'') || quoted.starts_with('"')) => {
+            let text = format!("\"{}\"", &quoted[1..quoted.len() - 1]);
+            if is_string { format!("String::from({})", text) } else { text }
+        },
+        _ => value.to_string(),
+    }
+}
+
+// rust has no default arguments, so every parameter is passed: a mock for each dependency,
+// the default where there is one and `todo!()` for whatever the test has to fill in
+fn rust_argument(param: &FunctionParameter, given: Option<&str>, mocked: Option<&str>) -> String {
+    match (given, &param.ptype, &param.default) {
+        (Some(value), ptype, _) => rust_value(value, ptype),
+        (None, ParameterType::Dependency(dep), _) if Some(dep.dependency_name.as_str()) == mocked => String::from("&mock"),
+        (None, ParameterType::Dependency(dep), _) => format!("&{}::default()", create_mock_name(&dep.dependency_name)),
+        (None, ptype, Some(default)) => rust_value(default, ptype),
+        (None, _, None) => String::from("todo!()"),
+    }
+}
+
+// async targets are driven to completion by the futures executor
+fn rust_call(target: &FunctionSignature, arguments: &str) -> String {
+    if target.is_async {
+        format!("block_on({}({}))", target.name, arguments)
+    } else {
+        format!("{}({})", target.name, arguments)
+    }
+}
+
+// `src/domains/user` is the module `domains::user`
+fn module_parts(path: &Path) -> Vec<String> {
+    path.strip_prefix("src").unwrap_or(path).iter().map(|part| part.to_string_lossy().to_string()).collect()
+}
+
+// `use std::collections::{HashMap};` for whichever collections the rendered code names, and the
+// either crate for unions
+pub fn type_imports(content: &str) -> Vec<String> {
+    let mut imports = vec![];
+    let used: Vec<&str> = ["HashMap", "HashSet"].iter().copied().filter(|name| content.contains(&format!("{}<", name))).collect();
+    if !used.is_empty() {
+        imports.push(format!("use std::collections::{{{}}};", used.join(", ")));
+    }
+    if content.contains("Either<") {
+        imports.push(String::from("use either::Either;"));
+    }
+    imports
+}
+
+// the mock stands in for every method of the dependency's trait
+pub fn cargo_mock_test(test: &MockTest, mock_config: &MockConfig, target: &FunctionSignature) -> String {
+    let arguments = target.input.iter()
+        .map(|param| rust_argument(param, None, Some(&mock_config.name)))
+        .collect::<Vec<String>>().join(", ");
+    format!("#[test]\nfn {}() {{\n    let mock = {} {{ response: {} }};\n    let result = {};\n    assert_eq!(1, 2);\n}}",
+        test.description.replace(" ", "_"), create_mock_name(&mock_config.name), test.mock_response, rust_call(target, &arguments))
+}
+
+pub fn cargo_call_test(test_name: &str, target: &FunctionSignature, arguments: &str) -> String {
+    format!("#[test]\nfn {}() {{\n    let result = {};\n    assert_eq!(1, 2);\n}}", test_name, rust_call(target, arguments))
+}
+
+// `names` from the module at `module`, e.g. `src/domains/user`, as the crate's tests and binary see it
+pub fn import_functions(crate_name: &str, module: &Path, names: &[String]) -> String {
+    format!("use {}::{}::{{{}}};", crate_name, module_parts(module).join("::"), names.join(", "))
+}
+
+// cargo builds every file directly under tests/ as a test crate of its own
+pub fn test_file(path: &Path) -> String {
+    format!("tests/{}.rs", module_parts(path).join("_"))
+}
+
+// the dependency traits when the file's signatures take any, and whichever imports the rendered types need
+pub fn support_imports(file: &ProcessedFile, functions: &str) -> Vec<String> {
+    let mut imports = vec![];
+    if !file.list_dependencies().is_empty() {
+        imports.push(String::from("use crate::dependencies::*;"));
+    }
+    imports.extend(type_imports(functions));
+    imports
+}
+
+// each mock answers every method of its dependency's trait with its canned response
+pub fn mock_implementations(dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+    dependencies.iter().map(|(dependency, methods)| {
+        let implementations: String = methods.iter()
+            .map(|method| format!("    fn {}(&self, _request: Value) -> DependencyResult {{\n        (self.response)()\n    }}\n", method))
+            .collect();
+        format!("impl {} for {} {{\n{}}}", dependency.trait_name(), create_mock_name(&dependency.dependency_name), implementations)
+    }).collect()
+}
+
+// a closure over the dependencies which takes the remaining arguments
+pub fn bind_dependencies(method: &ParsedMethod) -> String {
+    let remaining: Vec<&FunctionParameter> = method.ast.input.iter()
+        .filter(|param| !matches!(param.ptype, ParameterType::Dependency(_)) && param.default.is_none())
+        .collect();
+    let arguments = method.ast.input.iter().map(|param| match &param.ptype {
+        ParameterType::Dependency(dep) => format!("&{}", dep.dependency_name),
+        ptype => match &param.default {
+            Some(default) => rust_value(default, ptype),
+            None => param.name.clone(),
+        },
+    }).collect::<Vec<String>>().join(", ");
+    let closure_params = remaining.iter().map(|param| param.name.clone()).collect::<Vec<String>>().join(", ");
+    format!("let {} = |{}| {}({});\n", method.ast.name, closure_params, method.ast.name, arguments)
+}
+
+impl Rust {
+    // Rust has no default arguments, so keep the value visible for whoever implements the stub
+    fn render_parameter(&self, param: &FunctionParameter, mappings: &TypeMappings) -> String {
+        match &param.default {
+            Some(default) => format!("{}: {} /* = {} */", param.name, self.render_type(&param.ptype, mappings), default),
+            None => format!("{}: {}", param.name, self.render_type(&param.ptype, mappings)),
+        }
+    }
+
+    // the handler takes what the first step takes and returns what the last step produces,
+    // failing with the error type of the first step which can fail
+    fn workflow_types(&self, steps: &[(&ProcessedFile, &ParsedMethod)], mappings: &TypeMappings) -> (String, String, String) {
+        let result_children = |method: &ParsedMethod| match &method.ast.output {
+            ParameterType::ApplicationParentType(output) if output.type_name == "Result" && output.children.len() == 2 => Some(output.children.clone()),
+            _ => None,
+        };
+        let event_type = steps.first()
+            .and_then(|(_, method)| method.ast.input.iter().find(|param| !matches!(param.ptype, ParameterType::Dependency(_))))
+            .map(|param| self.render_type(&param.ptype, mappings))
+            .unwrap_or_else(|| String::from("()"));
+        let output_type = steps.last()
+            .map(|(_, method)| match result_children(method) {
+                Some(children) => self.render_type(&children[0], mappings),
+                None => self.render_type(&method.ast.output, mappings),
+            })
+            .unwrap_or_else(|| String::from("()"));
+        let error_type = steps.iter().find_map(|(_, method)| result_children(method))
+            .map(|children| self.render_type(&children[1], mappings))
+            .unwrap_or_else(|| String::from("String"));
+        (event_type, output_type, error_type)
+    }
+}
+
+impl Backend for Rust {
+    fn language(&self) -> &'static str {
+        "rust"
+    }
+    fn file_extension(&self) -> &'static str {
+        "rs"
+    }
+    fn template_set(&self) -> &'static str {
+        "rust"
+    }
+    fn mock_listing_set(&self) -> &'static str {
+        "rust"
+    }
+    // rust has its own Result in the standard library, so needs no result file
+    fn project_files(&self) -> &'static [(&'static str, &'static str)] {
+        &[("cargo.hbs", "Cargo.toml"), ("lib.hbs", "src/lib.rs")]
+    }
+    fn main_file(&self) -> &'static str {
+        "src/main.rs"
+    }
+    // cargo builds every file directly under tests/ as a test crate, shared code lives in a directory
+    fn mocks_file(&self) -> String {
+        String::from("tests/common/mod.rs")
+    }
+    // `src/a/b/c.rs` is declared as `pub mod c;` in src/a/b/mod.rs and `pub mod b;` in src/a/mod.rs,
+    // lib.rs declares the top level modules itself
+    fn module_declarations(&self, filename: &Path) -> Vec<(PathBuf, String)> {
+        let mut declarations = vec![];
+        if !filename.starts_with("src") || filename.extension().is_none_or(|extension| extension != "rs") {
+            return declarations;
+        }
+        let mut module = filename.with_extension("");
+        while let Some(parent) = module.parent().map(PathBuf::from) {
+            if parent.as_os_str().is_empty() || parent == Path::new("src") {
+                break;
+            }
+            let name = module.file_name().unwrap().to_string_lossy().to_string();
+            if name != "mod" {
+                declarations.push((parent.join("mod.rs"), format!("pub mod {};", name)));
+            }
+            module = parent;
+        }
+        declarations
+    }
+
+    fn qualified_type(&self, module_path: &[String], type_name: &str) -> String {
+        match module_path.last() {
+            Some(module) => format!("{}::{}", module, type_name),
+            None => type_name.to_string(),
+        }
+    }
+    fn optional_type(&self, inner: &str) -> String {
+        format!("Option<{}>", inner)
+    }
+    // Rust has no anonymous unions, so nest Either from the right: Either<A, Either<B, C>>
+    fn union_type(&self, members: &[String]) -> String {
+        let mut members = members.iter().rev().cloned();
+        let last = members.next().unwrap_or_default();
+        members.fold(last, |acc, member| format!("Either<{}, {}>", member, acc))
+    }
+    fn tuple_type(&self, members: &[String]) -> String {
+        format!("({})", members.join(", "))
+    }
+    fn function_type(&self, params: &[String], output: &str) -> String {
+        format!("Box<dyn Fn({}) -> {}>", params.join(", "), output)
+    }
+    // An enum would need a name the signature doesn't give, and would make the generated
+    // `rejects_invalid_*` tests impossible to write, so the values stay plain strings or integers
+    // and checking them is left to the implementation, as in python.
+    fn literal_type(&self, literal: &LiteralType) -> String {
+        if literal.is_numeric() { String::from("i64") } else { String::from("&'static str") }
+    }
+    // a trait object, so tests can hand in a mock
+    fn dependency_type(&self, dependency: &Dependency) -> String {
+        format!("&dyn {}", dependency.trait_name())
+    }
+
+    fn declare_record(&self, record: &RecordType, mappings: &TypeMappings) -> String {
+        let fields: String = record.fields.iter().map(|field| format!("    pub {}: {},\n", field.name, self.render_type(&field.ftype, mappings))).collect();
+        format!("#[derive(Debug, Clone, PartialEq)]\npub struct {} {{\n{}}}\n", record.name, fields)
+    }
+    fn declare_sum(&self, sum: &SumType, mappings: &TypeMappings) -> String {
+        let variants: String = sum.variants.iter().map(|variant| {
+            if variant.fields.is_empty() {
+                format!("    {},\n", variant.name)
+            } else {
+                let fields = variant.fields.iter().map(|field| format!("{}: {}", field.name, self.render_type(&field.ftype, mappings))).collect::<Vec<String>>();
+                format!("    {} {{ {} }},\n", variant.name, fields.join(", "))
+            }
+        }).collect();
+        format!("#[derive(Debug, Clone, PartialEq)]\npub enum {} {{\n{}}}\n", sum.name, variants)
+    }
+    fn declare_alias(&self, alias: &AliasType, mappings: &TypeMappings) -> String {
+        format!("pub type {} = {};\n", alias.name, self.render_type(&alias.target, mappings))
+    }
+    fn render_function(&self, signature: &FunctionSignature, mappings: &TypeMappings) -> String {
+        let parameters = signature.input.iter().map(|param| self.render_parameter(param, mappings)).collect::<Vec<String>>().join(", ");
+        let output = match self.render_type(&signature.output, mappings) {
+            output if output == "()" => String::new(),
+            output => format!(" -> {}", output),
+        };
+        format!("{}pub {}fn {}{}({}){} {{\n    todo!()\n}}\n",
+            signature.doc_comment("/// "), signature.async_prefix(), signature.name, signature.angle_type_parameters(), parameters, output)
+    }
+
+    fn domain_types_module(&self, definitions: &[String]) -> String {
+        let content = definitions.join("\n");
+        let imports = type_imports(&content);
+        if imports.is_empty() {
+            content
+        } else {
+            format!("{}\n\n{}", imports.join("\n"), content)
+        }
+    }
+    // the module is declared by its parent's mod.rs, or lib.rs at the top level
+    fn application_module(&self, project: &Project, file: &ProcessedFile) -> String {
+        let functions = file.methods.iter().map(|method| self.render_function(&method.ast, project.mappings)).collect::<Vec<String>>().join("\n");
+
+        let mut imports: Vec<String> = vec![];
+        if !project.types.is_empty() {
+            imports.push(String::from("use crate::domain_types::*;"));
+        }
+        imports.extend(support_imports(file, &functions));
+        imports.extend(file.list_type_modules().iter().map(|module_path| format!("use {};", module_path.join("::"))));
+        with_imports(&imports, functions)
+    }
+    // a trait per dependency, with a client to fill in
+    fn dependency_declarations(&self, dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+        dependencies.iter().map(|(dependency, methods)| {
+            let declarations: String = methods.iter().map(|method| format!("    fn {}(&self, request: Value) -> DependencyResult;\n", method)).collect();
+            let implementations: String = methods.iter()
+                .map(|method| format!("    fn {}(&self, _request: Value) -> DependencyResult {{\n        todo!()\n    }}\n", method))
+                .collect();
+            let name = dependency.trait_name();
+            format!("pub trait {} {{\n{}}}\n\npub struct {}Client;\n\nimpl {} for {}Client {{\n{}}}\n", name, declarations, name, name, name, implementations)
+        }).collect()
+    }
+    fn mock_list(&self, configs: &[&MockConfig], dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+        let mut mock_list: Vec<String> = configs.iter().map(|config| config.mock.clone()).collect();
+        mock_list.extend(mock_implementations(dependencies));
+        mock_list
+    }
+
+    fn render_mock_test(&self, test: &MockTest, _mock_refs: &[String], mock_config: &MockConfig, target: &FunctionSignature) -> String {
+        cargo_mock_test(test, mock_config, target)
+    }
+    // callers always pass every argument, so there's no case leaving the defaulted ones out
+    fn test_module(&self, project: &Project, file: &ProcessedFile, methods_and_mocks: &[MethodAndMocks], is_main: bool) -> Option<TestFile> {
+        let mut tests: Vec<String> = methods_and_mocks.iter().flat_map(|method_and_mocks| mock_tests(self, method_and_mocks, is_main)).collect();
+        tests.extend(file.methods.iter().flat_map(|method| literal_tests(self, method, Arguments::Every)));
+
+        let mut imports = mock_imports(methods_and_mocks);
+        if !mock_names(file).is_empty() {
+            imports.push(String::from("mod common;\nuse common::*;"));
+        }
+        if file.methods.iter().any(|method| method.ast.is_async) {
+            imports.push(String::from("use futures::executor::block_on;"));
+        }
+        let local_imports = vec![import_functions(&project.crate_name(), &file.path, &file.method_names())];
+        Some(TestFile::new(test_file(&file.path), &imports, &local_imports, &tests))
+    }
+    // the workflow lives in the binary, which the generated tests can't reach
+    fn main_test(&self, _project: &Project, _mock_names: &[String]) -> Option<TestFile> {
+        None
+    }
+    fn main_context(&self, project: &Project, workflow: &Workflow) -> Context {
+        let crate_name = project.crate_name();
+        let mut context = workflow.bound_context(|file| import_functions(&crate_name, &file.path, &file.method_names()), bind_dependencies);
+        let (event_type, output_type, error_type) = self.workflow_types(&workflow.steps, project.mappings);
+        context.insert("crate_name", &crate_name);
+        context.insert("has_domain_types", &!project.types.is_empty());
+        context.insert("event_type", &event_type);
+        context.insert("output_type", &output_type);
+        context.insert("error_type", &error_type);
+        context
+    }
+}
+
+impl CallTests for Rust {
+    fn test_argument(&self, param: &FunctionParameter, given: Option<String>) -> String {
+        rust_argument(param, given.as_deref(), None)
+    }
+    fn call_test(&self, test_name: &str, _description: &str, target: &FunctionSignature, arguments: &str) -> String {
+        cargo_call_test(test_name, target, arguments)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tera::Tera;
+    use crate::signature_parser::{ parse_signature, parse_type_definition };
+
+    #[test]
+    fn render_function_test() {
+        let render = |signature: &str| Rust.render_function(&parse_signature(signature).unwrap(), &TypeMappings::standard());
+        assert_eq!(render("async get_account(id: AccountId) -> Account"), "pub async fn get_account(id: AccountId) -> Account {\n    todo!()\n}\n");
+        assert_eq!(render("first<T>(items: List[T]) -> Optional[T]"), "pub fn first<T>(items: Vec<T>) -> Option<T> {\n    todo!()\n}\n");
+        assert_eq!(render("#: Fetches every user of the account\nget_users() -> Users"), "/// Fetches every user of the account\npub fn get_users() -> Users {\n    todo!()\n}\n");
+        assert_eq!(
+            render("update_user(dynamo: {dynamodb: update_item}, limit: Int = 100) -> None"),
+            "pub fn update_user(dynamo: &dyn Dynamodb, limit: i64 /* = 100 */) {\n    todo!()\n}\n"
+        );
+    }
+
+    #[test]
+    fn render_type_test() {
+        let signature = parse_signature(
+            "f(a: User?, b: User | Admin | Guest, c: (AccountId, User), d: Literal['active', 'disabled'], e: boto3.session.Session, f: (AccountId) -> Result[User, ErrorMsg]) -> None"
+        ).unwrap();
+        let mappings = TypeMappings::standard();
+        let types: Vec<String> = signature.input.iter().map(|param| Rust.render_type(&param.ptype, &mappings)).collect();
+        assert_eq!(types, vec![
+            "Option<User>", "Either<User, Either<Admin, Guest>>", "(AccountId, User)", "&'static str", "session::Session",
+            "Box<dyn Fn(AccountId) -> Result<User, ErrorMsg>>",
+        ]);
+    }
+
+    #[test]
+    fn declare_types_test() {
+        let declare = |definition: &str| Rust.render_type_definition(&parse_type_definition(definition).unwrap(), &TypeMappings::standard());
+        assert_eq!(declare("User { id: UserId, tags: Set[String] }"), "#[derive(Debug, Clone, PartialEq)]\npub struct User {\n    pub id: UserId,\n    pub tags: HashSet<String>,\n}\n");
+        assert_eq!(declare("Status = Active | Disabled { reason: String }"), "#[derive(Debug, Clone, PartialEq)]\npub enum Status {\n    Active,\n    Disabled { reason: String },\n}\n");
+        assert_eq!(declare("UserIds = List[UserId]"), "pub type UserIds = Vec<UserId>;\n");
+    }
+
+    #[test]
+    fn module_declarations_test() {
+        assert_eq!(Rust.module_declarations(Path::new("src/domains/users/account.rs")), vec![
+            (PathBuf::from("src/domains/users/mod.rs"), String::from("pub mod account;")),
+            (PathBuf::from("src/domains/mod.rs"), String::from("pub mod users;")),
+        ]);
+        assert!(Rust.module_declarations(Path::new("src/main.rs")).is_empty());
+        assert!(Rust.module_declarations(Path::new("tests/common/mod.rs")).is_empty());
+    }
+
+    #[test]
+    fn type_imports_test() {
+        assert_eq!(
+            type_imports("pub type Contact = Either<Email, HashSet<UserId>>;\n"),
+            vec!["use std::collections::{HashSet};", "use either::Either;"]
+        );
+        assert_eq!(type_imports("pub fn count_users(users: Vec<User>) -> i64"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn rust_mock_test() {
+        let target = parse_signature("async get_account(os: {os}, id: AccountId, name: String = 'main', limit: Int = 100) -> Account").unwrap();
+        let config = MockConfig {
+            name: String::from("os"),
+            import_statement: String::new(),
+            imports: vec![],
+            methods: vec![String::from("environ")],
+            mock: String::new(),
+            tests: vec![],
+        };
+        let test = MockTest { description: String::from("handle missing env var"), mock_response: String::from("|| Err(\"missing\".into())") };
+        assert_eq!(
+            cargo_mock_test(&test, &config, &target),
+            "#[test]\nfn handle_missing_env_var() {\n    let mock = OsMock { response: || Err(\"missing\".into()) };\n    let result = block_on(get_account(&mock, todo!(), String::from(\"main\"), 100));\n    assert_eq!(1, 2);\n}"
+        )
+    }
+
+    #[test]
+    fn bind_dependencies_test() {
+        let signature = "get_users(http_client: {requests: get}, ids: List[UserId], status: Literal['active'] = 'active') -> Users";
+        let method = ParsedMethod { raw: signature.to_string(), ast: parse_signature(signature).unwrap() };
+        assert_eq!(bind_dependencies(&method), "let get_users = |ids| get_users(&requests, ids, \"active\");\n");
+        let file = ProcessedFile::new(PathBuf::from("src/domains/users"), vec![method]);
+        assert_eq!(import_functions("my_project", &file.path, &file.method_names()), "use my_project::domains::users::{get_users};");
+        assert_eq!(test_file(&file.path), "tests/domains_users.rs");
+    }
+
+    #[test]
+    fn run_workflow_template_test() {
+        let mut tera = Tera::default();
+        tera.add_raw_template("main.hbs", include_str!("../../project_repository/templates/rust/main.hbs")).unwrap();
+        let mut context = Context::new();
+        context.insert("application_files", &Vec::<String>::new());
+        context.insert("dependencies", &Vec::<String>::new());
+        context.insert("functions_with_side_effects", &Vec::<String>::new());
+        context.insert("crate_name", "my_project");
+        context.insert("has_domain_types", &false);
+        context.insert("event_type", "DynamoStreamEvent");
+        context.insert("output_type", "()");
+        context.insert("error_type", "ErrorMsg");
+        context.insert("workflow_is_async", &true);
+        context.insert("workflow", &["validate_input", "update_user", "persist_users"]);
+        context.insert("async_steps", &["update_user"]);
+        context.insert("result_steps", &["validate_input", "update_user"]);
+        let output = tera.render("main.hbs", &context).unwrap();
+        // `?` hands a failed step's error back, the rest of the workflow doesn't run
+        let expected = "async fn handler(event: DynamoStreamEvent) -> Result<(), ErrorMsg> {";
+        assert!(output.contains(expected), "{}", output);
+        let expected = "    let value = event;\n    let value = validate_input(value)?;\n    let value = update_user(value).await?;\n    let value = persist_users(value);\n    Ok(value)";
+        assert!(output.contains(expected), "{}", output)
+    }
+}
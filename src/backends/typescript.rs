@@ -0,0 +1,331 @@
+use std::path::{ Path, PathBuf };
+use tera::Context;
+
+use crate::backends::{
+    default_argument_test, literal_tests, mentions_type, mock_imports, mock_names, mock_tests, relative_module_path, test_path, with_imports,
+    Arguments, Backend, CallTests, Project, TestFile, Workflow
+};
+use crate::backends::javascript::{ bind_dependencies, javascript_value, jest_argument, jest_call_test, jest_mock_test, jsdoc, render_handler_test };
+use crate::domains::file::ProcessedFile;
+use crate::domains::function_signature::{ Dependency, FunctionParameter, FunctionSignature, LiteralType, ParameterType };
+use crate::domains::schema::{ MethodAndMocks, MockConfig, MockTest };
+use crate::domains::type_definition::{ AliasType, RecordType, SumType };
+use crate::domains::type_mapping::TypeMappings;
+
+
+#[derive(Debug)]
+pub struct TypeScript;
+
+// a missing argument is `undefined` in typescript, `null` wouldn't satisfy a strict parameter type
+fn typescript_value(value: &str) -> &str {
+    match value {
+        "None" => "undefined",
+        _ => javascript_value(value),
+    }
+}
+
+pub fn import_module(module_path: &[String]) -> String {
+    format!("import * as {} from '{}';", module_path.last().cloned().unwrap_or_default(), module_path.join("/"))
+}
+
+// `names` from the module at `module`, e.g. `src/domains/user`, imported into a file in `from_dir`
+pub fn import_functions(from_dir: &Path, module: &Path, names: &[String]) -> String {
+    format!("import {{ {} }} from '{}';", names.join(", "), relative_module_path(from_dir, module))
+}
+
+// functions with dependencies are imported under another name, the partially applied ones take theirs
+pub fn import_workflow_functions(file: &ProcessedFile) -> String {
+    let names: Vec<String> = file.methods.iter().map(|method| {
+        if method.list_dependencies().is_empty() {
+            method.ast.name.clone()
+        } else {
+            format!("{} as {}_unbound", method.ast.name, method.ast.name)
+        }
+    }).collect();
+    import_functions(Path::new("src"), &file.path, &names)
+}
+
+pub fn import_domain_types(from_dir: &Path, type_names: &[&str]) -> String {
+    let names: Vec<String> = type_names.iter().map(|name| name.to_string()).collect();
+    import_functions(from_dir, Path::new("src/domain_types"), &names)
+}
+
+// the dependency interfaces an application file's signatures name, and Result when one uses it
+pub fn support_imports(file: &ProcessedFile) -> Vec<String> {
+    let from_dir = file.path.parent().unwrap_or(&file.path);
+    let mut imports = vec![];
+    let interfaces = file.list_dependency_types();
+    if !interfaces.is_empty() {
+        imports.push(import_functions(from_dir, Path::new("src/dependencies"), &interfaces));
+    }
+    if file.methods.iter().any(|method| method.ast.uses_type("Result")) {
+        imports.push(import_functions(from_dir, Path::new("src/result"), &[String::from("Result")]));
+    }
+    imports
+}
+
+impl TypeScript {
+    fn render_parameter(&self, param: &FunctionParameter, mappings: &TypeMappings) -> String {
+        match &param.default {
+            Some(default) => format!("{}: {} = {}", param.name, self.render_type(&param.ptype, mappings), typescript_value(default)),
+            None => format!("{}: {}", param.name, self.render_type(&param.ptype, mappings)),
+        }
+    }
+}
+
+impl Backend for TypeScript {
+    fn language(&self) -> &'static str {
+        "typescript"
+    }
+    fn file_extension(&self) -> &'static str {
+        "ts"
+    }
+    fn template_set(&self) -> &'static str {
+        "typescript"
+    }
+    fn mock_listing_set(&self) -> &'static str {
+        "typescript"
+    }
+    fn project_files(&self) -> &'static [(&'static str, &'static str)] {
+        &[("package.hbs", "package.json"), ("tsconfig.hbs", "tsconfig.json"), ("jest_config.hbs", "jest.config.js"), ("result.hbs", "src/result.ts")]
+    }
+    fn main_file(&self) -> &'static str {
+        "src/index.ts"
+    }
+    // modules declare their exports themselves, so need no marker file
+    fn module_declarations(&self, _filename: &Path) -> Vec<(PathBuf, String)> {
+        vec![]
+    }
+
+    fn optional_type(&self, inner: &str) -> String {
+        format!("{} | undefined", inner)
+    }
+    fn union_type(&self, members: &[String]) -> String {
+        members.join(" | ")
+    }
+    fn tuple_type(&self, members: &[String]) -> String {
+        format!("[{}]", members.join(", "))
+    }
+    fn function_type(&self, params: &[String], output: &str) -> String {
+        let params = params.iter().enumerate().map(|(index, param)| format!("arg{}: {}", index, param)).collect::<Vec<String>>();
+        format!("({}) => {}", params.join(", "), output)
+    }
+    fn literal_type(&self, literal: &LiteralType) -> String {
+        literal.values.join(" | ")
+    }
+    // the interface declared in the generated dependencies module
+    fn dependency_type(&self, dependency: &Dependency) -> String {
+        dependency.trait_name()
+    }
+
+    fn declare_record(&self, record: &RecordType, mappings: &TypeMappings) -> String {
+        let fields: String = record.fields.iter().map(|field| format!("    {}: {};\n", field.name, self.render_type(&field.ftype, mappings))).collect();
+        format!("export interface {} {{\n{}}}\n", record.name, fields)
+    }
+    // a tagged union, `kind` tells the variants apart
+    fn declare_sum(&self, sum: &SumType, mappings: &TypeMappings) -> String {
+        let variants = sum.variants.iter().map(|variant| {
+            let fields: String = variant.fields.iter().map(|field| format!("; {}: {}", field.name, self.render_type(&field.ftype, mappings))).collect();
+            format!("\n    | {{ kind: '{}'{} }}", variant.name, fields)
+        }).collect::<String>();
+        format!("export type {} ={};\n", sum.name, variants)
+    }
+    fn declare_alias(&self, alias: &AliasType, mappings: &TypeMappings) -> String {
+        format!("export type {} = {};\n", alias.name, self.render_type(&alias.target, mappings))
+    }
+    fn render_function(&self, signature: &FunctionSignature, mappings: &TypeMappings) -> String {
+        let parameters = signature.input.iter().map(|param| self.render_parameter(param, mappings)).collect::<Vec<String>>().join(", ");
+        let output = if signature.is_async {
+            format!("Promise<{}>", self.render_type(&signature.output, mappings))
+        } else {
+            self.render_type(&signature.output, mappings)
+        };
+        format!("{}export {}function {}{}({}): {} {{\n    throw new Error('not implemented');\n}}\n",
+            jsdoc(signature, &[]), signature.async_prefix(), signature.name, signature.angle_type_parameters(), parameters, output)
+    }
+
+    fn domain_types_module(&self, definitions: &[String]) -> String {
+        let content = definitions.join("\n");
+        if mentions_type(&content, "Result") {
+            format!("{}\n\n{}", import_functions(Path::new("src"), Path::new("src/result"), &[String::from("Result")]), content)
+        } else {
+            content
+        }
+    }
+    fn application_module(&self, project: &Project, file: &ProcessedFile) -> String {
+        let functions = file.methods.iter().map(|method| self.render_function(&method.ast, project.mappings)).collect::<Vec<String>>().join("\n");
+
+        let from_dir = file.path.parent().unwrap_or(&file.path);
+        let mut imports: Vec<String> = vec![];
+        if !project.types.is_empty() {
+            imports.push(import_domain_types(from_dir, &project.type_names()));
+        }
+        imports.extend(support_imports(file));
+        imports.extend(file.list_type_modules().iter().map(|module_path| import_module(module_path)));
+        with_imports(&imports, functions)
+    }
+    // an interface per dependency, naming the methods the application calls on it
+    fn dependency_declarations(&self, dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+        dependencies.iter().map(|(dependency, methods)| {
+            let declarations: String = methods.iter().map(|method| format!("    {}(...args: any[]): any;\n", method)).collect();
+            format!("export interface {} {{\n{}}}\n", dependency.trait_name(), declarations)
+        }).collect()
+    }
+    fn mock_list(&self, configs: &[&MockConfig], _dependencies: &[(Dependency, Vec<String>)]) -> Vec<String> {
+        configs.iter().map(|config| config.mock.clone()).collect()
+    }
+
+    // ts-jest type checks the tests, the placeholders have to satisfy any parameter type
+    fn render_mock_test(&self, test: &MockTest, mock_refs: &[String], _mock_config: &MockConfig, target: &FunctionSignature) -> String {
+        jest_mock_test(test, mock_refs, target, "{} as any")
+    }
+    fn test_module(&self, _project: &Project, file: &ProcessedFile, methods_and_mocks: &[MethodAndMocks], is_main: bool) -> Option<TestFile> {
+        let mut tests: Vec<String> = methods_and_mocks.iter().flat_map(|method_and_mocks| mock_tests(self, method_and_mocks, is_main)).collect();
+        tests.extend(file.methods.iter().flat_map(|method| default_argument_test(self, method)));
+        tests.extend(file.methods.iter().flat_map(|method| literal_tests(self, method, Arguments::Positional)));
+
+        let test_file_path = test_path(file);
+        let test_dir = Path::new(&test_file_path).parent().unwrap_or_else(|| Path::new(""));
+        let mut imports = mock_imports(methods_and_mocks);
+        let mock_names = mock_names(file);
+        if !mock_names.is_empty() {
+            imports.push(import_functions(test_dir, Path::new("tests/mocks"), &mock_names));
+        }
+        let local_imports = vec![import_functions(test_dir, &file.path, &file.method_names())];
+        Some(TestFile::new(format!("{}.test.ts", test_file_path), &imports, &local_imports, &tests))
+    }
+    fn main_test(&self, _project: &Project, _mock_names: &[String]) -> Option<TestFile> {
+        let handler_import = import_functions(Path::new("tests"), Path::new("src/index"), &[String::from("handler")]);
+        Some(TestFile::new(String::from("tests/index.test.ts"), &[], &[handler_import], &[render_handler_test()]))
+    }
+    fn main_context(&self, _project: &Project, workflow: &Workflow) -> Context {
+        workflow.bound_context(import_workflow_functions, bind_dependencies)
+    }
+}
+
+impl CallTests for TypeScript {
+    // a value outside the literal's type is deliberate, so typescript has to be told to let it through
+    fn test_argument(&self, param: &FunctionParameter, given: Option<String>) -> String {
+        let given = match (given, &param.ptype) {
+            (Some(value), ParameterType::LiteralType(literal)) if !literal.values.contains(&value) => Some(format!("{} as any", value)),
+            (given, _) => given,
+        };
+        jest_argument(param, given, "{} as any")
+    }
+    fn call_test(&self, _test_name: &str, description: &str, target: &FunctionSignature, arguments: &str) -> String {
+        jest_call_test(description, target, arguments)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tera::Tera;
+    use crate::domains::method::ParsedMethod;
+    use crate::signature_parser::{ parse_signature, parse_type_definition };
+
+    fn file(signatures: &[&str]) -> ProcessedFile {
+        let methods = signatures.iter()
+            .map(|signature| ParsedMethod { raw: signature.to_string(), ast: parse_signature(signature).unwrap() })
+            .collect();
+        ProcessedFile::new(PathBuf::from("src/domains/users"), methods)
+    }
+
+    #[test]
+    fn render_function_test() {
+        let render = |signature: &str| TypeScript.render_function(&parse_signature(signature).unwrap(), &TypeMappings::standard());
+        assert_eq!(
+            render("async get_account(id: AccountId) -> Account"),
+            "export async function get_account(id: AccountId): Promise<Account> {\n    throw new Error('not implemented');\n}\n"
+        );
+        assert_eq!(
+            render("first<T>(items: List[T]) -> Optional[T]"),
+            "export function first<T>(items: T[]): T | undefined {\n    throw new Error('not implemented');\n}\n"
+        );
+        assert_eq!(
+            render("update_user(dynamo: {dynamodb: update_item, put_item}, http: {requests}, limit: Optional[Int] = None) -> None"),
+            "export function update_user(dynamo: Dynamodb, http: Requests, limit: number | undefined = undefined): void {\n    throw new Error('not implemented');\n}\n"
+        );
+        assert_eq!(
+            render("retry(op: (AccountId) -> Result[User, ErrorMsg]) -> (User, Literal['a', 'b'])"),
+            "export function retry(op: (arg0: AccountId) => Result<User, ErrorMsg>): [User, 'a' | 'b'] {\n    throw new Error('not implemented');\n}\n"
+        );
+    }
+
+    #[test]
+    fn declare_types_test() {
+        let declare = |definition: &str| TypeScript.render_type_definition(&parse_type_definition(definition).unwrap(), &TypeMappings::standard());
+        assert_eq!(declare("User { id: UserId, email: Email }"), "export interface User {\n    id: UserId;\n    email: Email;\n}\n");
+        assert_eq!(declare("Status = Active | Disabled { reason: String }"), "export type Status =\n    | { kind: 'Active' }\n    | { kind: 'Disabled'; reason: string };\n");
+        assert_eq!(declare("UserIds = List[UserId]"), "export type UserIds = UserId[];\n");
+    }
+
+    #[test]
+    fn typescript_mock_test() {
+        let target = parse_signature("get_account(os: {os}, id: AccountId) -> Account").unwrap();
+        let test = MockTest { description: String::from("handle missing env var"), mock_response: String::from("mockReturnValue(undefined)") };
+        // ts-jest type checks the tests, the placeholders have to satisfy any parameter type
+        assert_eq!(
+            jest_mock_test(&test, &[String::from("OsMock.environ")], &target, "{} as any"),
+            "it('handle missing env var', () => {\n    jest.spyOn(OsMock, 'environ').mockReturnValue(undefined);\n    const result = get_account(OsMock, {} as any);\n    expect(1).toBe(2);\n});"
+        )
+    }
+
+    #[test]
+    fn typescript_literal_tests_test() {
+        let signature = "set_level(client: {requests}, level: Literal['active', 'disabled']) -> None";
+        let method = ParsedMethod { raw: String::from(signature), ast: parse_signature(signature).unwrap() };
+        let tests = literal_tests(&TypeScript, &method, Arguments::Positional);
+        assert_eq!(tests[0], "it('accepts active as level', () => {\n    const result = set_level(RequestsMock, 'active');\n    expect(1).toBe(2);\n});");
+        assert_eq!(tests[2], "it('rejects an invalid level', () => {\n    const result = set_level(RequestsMock, '__invalid__' as any);\n    expect(1).toBe(2);\n});");
+    }
+
+    #[test]
+    fn support_imports_test() {
+        let users = file(&[
+            "get_users(http_client: {requests: get}, ids: List[UserId]) -> Result[List[User], ErrorMsg]",
+            "update_users(dynamo: {dynamodb: update_item}, http_client: {requests}, users: List[User]) -> None",
+        ]);
+        assert_eq!(support_imports(&users), vec![
+            "import { Requests, Dynamodb } from '../dependencies';", "import { Result } from '../result';",
+        ]);
+        assert!(support_imports(&file(&["count_users(users: List[User]) -> Int"])).is_empty());
+
+        let dependencies = users.list_dependencies().into_iter().map(|dep| (dep.clone(), dep.methods.clone())).collect::<Vec<_>>();
+        assert_eq!(
+            TypeScript.dependency_declarations(&dependencies[..2]),
+            vec!["export interface Requests {\n    get(...args: any[]): any;\n}\n", "export interface Dynamodb {\n    update_item(...args: any[]): any;\n}\n"]
+        );
+    }
+
+    #[test]
+    fn run_workflow_template_test() {
+        let mut tera = Tera::default();
+        tera.add_raw_template("main.hbs", include_str!("../../project_repository/templates/typescript/main.hbs")).unwrap();
+        let mut context = Context::new();
+        context.insert("application_files", &Vec::<String>::new());
+        context.insert("dependencies", &Vec::<String>::new());
+        context.insert("functions_with_side_effects", &Vec::<String>::new());
+        context.insert("workflow", &["validate_input", "update_user", "persist_users"]);
+        context.insert("async_steps", &["update_user"]);
+        context.insert("result_steps", &["validate_input", "update_user"]);
+        let output = tera.render("main.hbs", &context).unwrap();
+        // a failed step is returned as it is, the rest of the workflow doesn't run
+        let expected = r#"    let value: any = event;
+    const validate_input_result = validate_input(value);
+    if (!validate_input_result.ok) {
+        return validate_input_result;
+    }
+    value = validate_input_result.value;
+    const update_user_result = await update_user(value);
+    if (!update_user_result.ok) {
+        return update_user_result;
+    }
+    value = update_user_result.value;
+    value = persist_users(value);
+    return ok(value);"#;
+        assert!(output.contains(expected), "{}", output)
+    }
+}
@@ -1,3 +1,28 @@
+pub enum Command {
+    Generate,
+    // rewrite the schema's signatures in canonical form, or only report whether they already are
+    Format { check: bool },
+}
+
 pub struct Cli {
     pub schema_path: std::path::PathBuf,
-}
\ No newline at end of file
+    pub command: Command,
+}
+
+impl Cli {
+    // `<schema>` generates the project, `fmt [--check] <schema>` formats the schema
+    pub fn from_args(args: &[String]) -> Cli {
+        let (command, rest) = match args.first().map(String::as_str) {
+            Some("fmt") => {
+                let check = args.iter().any(|arg| arg == "--check");
+                (Command::Format { check }, args[1..].iter().filter(|arg| *arg != "--check").collect::<Vec<&String>>())
+            },
+            _ => (Command::Generate, args.iter().collect()),
+        };
+        let schema_path = rest.first().expect("no path given");
+        Cli {
+            schema_path: std::path::PathBuf::from(schema_path),
+            command,
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use std::fmt;
+use std::path::PathBuf;
+use failure::Fail;
+
+
+// Where a signature failed to parse and what the parser wanted to see there.
+// `line` and `column` are 1-based, columns are counted in characters so they can be used to draw a caret.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SignatureError {
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+    pub expected: String,
+}
+
+// A SignatureError placed in the schema: which file, which entry in its `methods:` or `types:` list.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SignatureDiagnostic {
+    pub path: PathBuf,
+    pub section: &'static str,
+    pub index: usize,
+    pub signature: String,
+    pub error: SignatureError,
+}
+
+impl SignatureDiagnostic {
+    pub fn new(path: PathBuf, index: usize, signature: &str, error: SignatureError) -> SignatureDiagnostic {
+        SignatureDiagnostic { path, section: "methods", index, signature: signature.to_string(), error }
+    }
+
+    // `path` is empty for the types declared in the schema itself
+    pub fn for_type(path: PathBuf, index: usize, definition: &str, error: SignatureError) -> SignatureDiagnostic {
+        SignatureDiagnostic { path, section: "types", index, signature: definition.to_string(), error }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self.section {
+            "types" => "type definition",
+            _ => "method signature",
+        }
+    }
+}
+
+impl fmt::Display for SignatureDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let padding = " ".repeat(self.error.column - 1);
+        let carets = "^".repeat(self.error.length);
+        let line = self.signature.lines().nth(self.error.line - 1).unwrap_or("");
+        let location = if self.path.as_os_str().is_empty() {
+            format!("{}[{}]", self.section, self.index)
+        } else {
+            format!("{} {}[{}]", self.path.display(), self.section, self.index)
+        };
+        writeln!(f, "invalid {}: {}", self.kind(), self.error.expected)?;
+        writeln!(f, "  --> {}:{}:{}", location, self.error.line, self.error.column)?;
+        writeln!(f, "   |")?;
+        writeln!(f, "   | {}", line)?;
+        write!(f, "   | {}{}", padding, carets)
+    }
+}
+
+impl Fail for SignatureDiagnostic {}
+
+
+// Every signature problem found in a schema, so they can all be fixed in one go.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SchemaDiagnostics {
+    pub diagnostics: Vec<SignatureDiagnostic>,
+}
+
+impl fmt::Display for SchemaDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}\n", diagnostic)?;
+        }
+        let plural = if self.diagnostics.len() == 1 { "" } else { "s" };
+        let kind = match self.diagnostics.first() {
+            Some(first) if self.diagnostics.iter().all(|diagnostic| diagnostic.section == first.section) => first.kind(),
+            _ => "definition",
+        };
+        write!(f, "schema has {} invalid {}{}", self.diagnostics.len(), kind, plural)
+    }
+}
+
+impl Fail for SchemaDiagnostics {}
+
+
+// A type named in a signature which is neither built in, declared nor a system type.
+#[derive(PartialEq, Debug, Clone)]
+pub struct UnknownTypeDiagnostic {
+    pub path: PathBuf,
+    pub method_name: String,
+    pub type_name: String,
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for UnknownTypeDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown type `{}` in {} ({})", self.type_name, self.method_name, self.path.display())?;
+        match &self.suggestion {
+            Some(suggestion) => write!(f, "\n   = help: did you mean `{}`?", suggestion),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Fail for UnknownTypeDiagnostic {}
+
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct TypeDiagnostics {
+    pub diagnostics: Vec<UnknownTypeDiagnostic>,
+}
+
+impl fmt::Display for TypeDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}\n", diagnostic)?;
+        }
+        let plural = if self.diagnostics.len() == 1 { "" } else { "s" };
+        write!(f, "schema refers to {} unknown type{}", self.diagnostics.len(), plural)
+    }
+}
+
+impl Fail for TypeDiagnostics {}
+
+
+// A signature the target language can't express, e.g. a go Result anywhere but the return type.
+#[derive(PartialEq, Debug, Clone)]
+pub struct LanguageDiagnostic {
+    pub path: PathBuf,
+    pub method_name: String,
+    pub language: String,
+    pub message: String,
+}
+
+impl fmt::Display for LanguageDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}) can't be written in {}: {}", self.method_name, self.path.display(), self.language, self.message)
+    }
+}
+
+impl Fail for LanguageDiagnostic {}
+
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct LanguageDiagnostics {
+    pub diagnostics: Vec<LanguageDiagnostic>,
+}
+
+impl fmt::Display for LanguageDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}\n", diagnostic)?;
+        }
+        let plural = if self.diagnostics.len() == 1 { "" } else { "s" };
+        write!(f, "schema has {} unsupported signature{}", self.diagnostics.len(), plural)
+    }
+}
+
+impl Fail for LanguageDiagnostics {}
+
+
+// Two neighbouring workflow steps whose types don't line up.
+#[derive(PartialEq, Debug, Clone)]
+pub struct WorkflowDiagnostic {
+    pub from_step: String,
+    pub to_step: String,
+    pub message: String,
+}
+
+impl fmt::Display for WorkflowDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "workflow step {} -> {}: {}", self.from_step, self.to_step, self.message)
+    }
+}
+
+impl Fail for WorkflowDiagnostic {}
+
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct WorkflowDiagnostics {
+    pub diagnostics: Vec<WorkflowDiagnostic>,
+}
+
+impl fmt::Display for WorkflowDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}", diagnostic)?;
+        }
+        let plural = if self.diagnostics.len() == 1 { "" } else { "es" };
+        write!(f, "\nworkflow has {} type mismatch{}", self.diagnostics.len(), plural)
+    }
+}
+
+impl Fail for WorkflowDiagnostics {}
+
+
+// A workflow step which doesn't name a method, or a method name used more than once.
+#[derive(PartialEq, Debug, Clone)]
+pub enum NameDiagnostic {
+    UnknownStep { step: String, suggestion: Option<String> },
+    DuplicateMethod { method_name: String, paths: Vec<PathBuf> },
+}
+
+impl fmt::Display for NameDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownStep { step, suggestion } => {
+                write!(f, "workflow step `{}` does not match any method", step)?;
+                match suggestion {
+                    Some(suggestion) => write!(f, "\n   = help: did you mean `{}`?", suggestion),
+                    None => Ok(()),
+                }
+            },
+            Self::DuplicateMethod { method_name, paths } => {
+                let paths = paths.iter().map(|path| path.display().to_string()).collect::<Vec<String>>();
+                write!(f, "method `{}` is declared more than once, in {}", method_name, paths.join(", "))
+            },
+        }
+    }
+}
+
+impl Fail for NameDiagnostic {}
+
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct NameDiagnostics {
+    pub diagnostics: Vec<NameDiagnostic>,
+}
+
+impl fmt::Display for NameDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}\n", diagnostic)?;
+        }
+        let plural = if self.diagnostics.len() == 1 { "" } else { "s" };
+        write!(f, "schema has {} naming problem{}", self.diagnostics.len(), plural)
+    }
+}
+
+impl Fail for NameDiagnostics {}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_diagnostic_display_test() {
+        let diagnostic = SignatureDiagnostic::new(
+            PathBuf::from("src/validator"),
+            0,
+            "validate_input(input Dict) -> ErrorMsg",
+            SignatureError { line: 1, column: 22, length: 4, expected: String::from("expected `:` after the parameter name") },
+        );
+        let expected = r#"invalid method signature: expected `:` after the parameter name
+  --> src/validator methods[0]:1:22
+   |
+   | validate_input(input Dict) -> ErrorMsg
+   |                      ^^^^"#;
+        assert_eq!(diagnostic.to_string(), expected)
+    }
+
+    #[test]
+    fn schema_diagnostics_display_test() {
+        let diagnostic = |index, signature: &str, column| SignatureDiagnostic::new(
+            PathBuf::from("src/domains/account"),
+            index,
+            signature,
+            SignatureError { line: 1, column, length: 1, expected: String::from("expected `->` followed by the return type") },
+        );
+        let diagnostics = SchemaDiagnostics {
+            diagnostics: vec![diagnostic(0, "get_account(id: AccountId)", 27), diagnostic(2, "get_user(id: UserId)", 21)],
+        };
+        let result = diagnostics.to_string();
+        assert!(result.contains("methods[0]:1:27"));
+        assert!(result.contains("methods[2]:1:21"));
+        assert!(result.ends_with("schema has 2 invalid method signatures"))
+    }
+
+    #[test]
+    fn unknown_type_diagnostic_display_test() {
+        let diagnostic = UnknownTypeDiagnostic {
+            path: PathBuf::from("src/domains/account"),
+            method_name: String::from("get_account"),
+            type_name: String::from("Acount"),
+            suggestion: Some(String::from("Account")),
+        };
+        let expected = "unknown type `Acount` in get_account (src/domains/account)\n   = help: did you mean `Account`?";
+        assert_eq!(diagnostic.to_string(), expected)
+    }
+
+    #[test]
+    fn type_diagnostic_display_test() {
+        let diagnostic = SignatureDiagnostic::for_type(
+            PathBuf::new(),
+            1,
+            "User : { id: UserId }",
+            SignatureError { line: 1, column: 6, length: 1, expected: String::from("expected `{` or `=` after the type name") },
+        );
+        let result = diagnostic.to_string();
+        assert!(result.starts_with("invalid type definition: expected `{` or `=` after the type name"));
+        assert!(result.contains("  --> types[1]:1:6"))
+    }
+}
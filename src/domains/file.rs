@@ -1,11 +1,11 @@
 use serde::{Deserialize, Serialize};
-use crate::domains::method::ParsedMethod;
+use crate::domains::method::{ ParsedMethod, UnprocessedMethod };
 use crate::domains::function_signature::Dependency;
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct UnprocessedFile {
     pub path: std::path::PathBuf,
-    pub methods: Vec<String>,
+    pub methods: Vec<UnprocessedMethod>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
@@ -23,6 +23,39 @@ impl ProcessedFile {
         self.methods.iter().flat_map(|method| method.ast.list_dependencies()).collect()
     }
 
+    // the name each dependency is declared under, once per dependency
+    pub fn list_dependency_types(&self) -> Vec<String> {
+        let mut names: Vec<String> = vec![];
+        for name in self.list_dependencies().iter().map(|dep| dep.trait_name()) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    pub fn method_names(&self) -> Vec<String> {
+        self.methods.iter().map(|method| method.ast.name.clone()).collect()
+    }
+
+    // every type parameter the methods declare, in order of first appearance
+    pub fn list_type_parameters(&self) -> Vec<&String> {
+        let mut type_parameters: Vec<&String> = vec![];
+        for type_parameter in self.methods.iter().flat_map(|method| method.ast.type_parameters.iter()) {
+            if !type_parameters.contains(&type_parameter) {
+                type_parameters.push(type_parameter);
+            }
+        }
+        type_parameters
+    }
+
+    pub fn list_type_modules(&self) -> Vec<Vec<String>> {
+        let mut modules: Vec<Vec<String>> = self.methods.iter().flat_map(|method| method.ast.list_type_modules()).collect();
+        modules.sort();
+        modules.dedup();
+        modules
+    }
+
     // python imports need you to set the syspath
     // then split the path after src and join back up with full-stops
 
@@ -31,3 +64,29 @@ impl ProcessedFile {
     // - file1 method name
     // - file2 path
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use crate::signature_parser::parse_signature;
+
+    #[test]
+    fn list_type_parameters_test() {
+        let methods = ["first<T>(items: List[T]) -> T", "pair<T, U>(left: T, right: U) -> (T, U)"].iter()
+            .map(|signature| ParsedMethod { raw: signature.to_string(), ast: parse_signature(signature).unwrap() })
+            .collect();
+        let file = ProcessedFile::new(PathBuf::from("src/pairs"), methods);
+        assert_eq!(file.list_type_parameters(), vec!["T", "U"]);
+    }
+
+    #[test]
+    fn list_type_modules_test() {
+        let methods = ["connect(session: boto3.Session) -> models.User", "find(id: Int) -> Optional[models.User]"].iter()
+            .map(|signature| ParsedMethod { raw: signature.to_string(), ast: parse_signature(signature).unwrap() })
+            .collect();
+        let file = ProcessedFile::new(PathBuf::from("src/users"), methods);
+        assert_eq!(file.list_type_modules(), vec![vec!["boto3"], vec!["models"]]);
+    }
+}
@@ -0,0 +1,326 @@
+use std::fmt;
+use exitfailure::ExitFailure;
+use failure::err_msg;
+
+use crate::domains::schema::Schema;
+use crate::domains::diagnostic::{ SchemaDiagnostics, SignatureDiagnostic };
+use crate::domains::function_signature::{
+    ApplicationType, ApplicationParentType, OptionalType, UnionType, TupleType, FunctionType, LiteralType,
+    FunctionParameter, FunctionSignature, Dependency, ParameterType
+};
+use crate::signature_parser::parse_signature;
+
+
+// The canonical text of a signature: what signature_parser reads, with one way of spacing everything.
+// Parsing the output of these always gives back the AST that was formatted.
+
+
+fn join<T: fmt::Display>(items: &[T], separator: &str) -> String {
+    items.iter().map(|item| item.to_string()).collect::<Vec<String>>().join(separator)
+}
+
+
+// unions and functions read differently once something is attached to them, so they get grouped
+fn grouped(ptype: &ParameterType) -> String {
+    match ptype {
+        ParameterType::UnionType(_) | ParameterType::Function(_) | ParameterType::OptionalType(_) => format!("({})", ptype),
+        _ => ptype.to_string(),
+    }
+}
+
+
+impl fmt::Display for ApplicationType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for module in &self.module_path {
+            write!(f, "{}.", module)?;
+        }
+        write!(f, "{}", self.type_name)
+    }
+}
+
+impl fmt::Display for ApplicationParentType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for module in &self.module_path {
+            write!(f, "{}.", module)?;
+        }
+        write!(f, "{}[{}]", self.type_name, join(&self.children, ", "))
+    }
+}
+
+impl fmt::Display for OptionalType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}?", grouped(&self.inner))
+    }
+}
+
+impl fmt::Display for UnionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let members = self.members.iter().map(|member| match member {
+            ParameterType::UnionType(_) | ParameterType::Function(_) => grouped(member),
+            _ => member.to_string(),
+        }).collect::<Vec<String>>();
+        write!(f, "{}", members.join(" | "))
+    }
+}
+
+impl fmt::Display for TupleType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.members.len() {
+            1 => write!(f, "({},)", self.members[0]),
+            _ => write!(f, "({})", join(&self.members, ", ")),
+        }
+    }
+}
+
+impl fmt::Display for FunctionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}) -> {}", join(&self.params, ", "), self.output)
+    }
+}
+
+impl fmt::Display for LiteralType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Literal[{}]", self.values.join(", "))
+    }
+}
+
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.methods.is_empty() {
+            write!(f, "{{{}}}", self.dependency_name)
+        } else {
+            write!(f, "{{{}: {}}}", self.dependency_name, self.methods.join(", "))
+        }
+    }
+}
+
+impl fmt::Display for ParameterType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ApplicationType(value) => value.fmt(f),
+            Self::ApplicationParentType(value) => value.fmt(f),
+            Self::OptionalType(value) => value.fmt(f),
+            Self::UnionType(value) => value.fmt(f),
+            Self::TupleType(value) => value.fmt(f),
+            Self::Function(value) => value.fmt(f),
+            Self::LiteralType(value) => value.fmt(f),
+            Self::Dependency(value) => value.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for FunctionParameter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.ptype)?;
+        match &self.default {
+            Some(default) => write!(f, " = {}", default),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for FunctionSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(description) = &self.description {
+            writeln!(f, "#: {}", description)?;
+        }
+        if self.is_async {
+            write!(f, "async ")?;
+        }
+        write!(f, "{}", self.name)?;
+        if !self.type_parameters.is_empty() {
+            write!(f, "<{}>", self.type_parameters.join(", "))?;
+        }
+        write!(f, "({}) -> {}", join(&self.input, ", "), self.output)
+    }
+}
+
+
+// Rewrites the `methods:` entries of a schema in its canonical signature format.
+// Only the signatures change, the rest of the file keeps its comments and layout.
+pub fn format_schema_source(source: &str) -> Result<String, ExitFailure> {
+    let schema: Schema = serde_yaml::from_str(source)?;
+
+    let mut lines: Vec<String> = source.lines().map(String::from).collect();
+    let mut cursor = 0;
+    let mut diagnostics = vec![];
+    for file in &schema.files {
+        for (index, method) in file.methods.iter().enumerate() {
+            let original = method.signature();
+            let formatted = match parse_signature(original) {
+                Ok(ast) => ast.to_string(),
+                Err(error) => {
+                    diagnostics.push(SignatureDiagnostic::new(file.path.clone(), index, original, error));
+                    continue;
+                },
+            };
+            cursor = replace_signature(&mut lines, cursor, original, &formatted).ok_or_else(|| {
+                err_msg(format!("could not find the signature of {} methods[{}] in the schema", file.path.display(), index))
+            })?;
+        }
+    }
+    if !diagnostics.is_empty() {
+        return Err(SchemaDiagnostics { diagnostics }.into());
+    }
+
+    let mut formatted = lines.join("\n");
+    if source.ends_with('\n') {
+        formatted.push('\n');
+    }
+    Ok(formatted)
+}
+
+
+fn single_quoted(signature: &str) -> String {
+    format!("'{}'", signature.replace("'", "''"))
+}
+
+
+fn double_quoted(signature: &str) -> String {
+    format!("\"{}\"", signature.replace("\\", "\\\\").replace("\"", "\\\""))
+}
+
+
+// Find `original` as it was written in the YAML, from line `cursor` onwards, and swap in `formatted`.
+// Returns the line after the replacement so the next signature is searched for after this one.
+fn replace_signature(lines: &mut Vec<String>, cursor: usize, original: &str, formatted: &str) -> Option<usize> {
+    if original.contains('\n') {
+        return replace_block_signature(lines, cursor, original, formatted);
+    }
+
+    let quoted = [(single_quoted(original), single_quoted(formatted)), (double_quoted(original), double_quoted(formatted))];
+    for (index, line) in lines.iter_mut().enumerate().skip(cursor) {
+        if let Some((from, to)) = quoted.iter().find(|(from, _)| line.contains(from.as_str())) {
+            *line = line.replacen(from.as_str(), to, 1);
+            return Some(index + 1);
+        }
+        // a plain scalar runs to the end of the line, quote it in case the canonical form needs it
+        let trimmed = line.trim_end();
+        if trimmed.ends_with(&format!("- {}", original)) || trimmed.ends_with(&format!(": {}", original)) {
+            *line = format!("{}{}", &trimmed[..trimmed.len() - original.len()], single_quoted(formatted));
+            return Some(index + 1);
+        }
+        // a one line `|` block keeps the signature unquoted on its own line
+        if trimmed.trim_start() == original {
+            *line = format!("{}{}", &trimmed[..trimmed.len() - original.len()], formatted);
+            return Some(index + 1);
+        }
+    }
+    None
+}
+
+
+// a `|` block: every line of the signature sits at the same indentation
+fn replace_block_signature(lines: &mut Vec<String>, cursor: usize, original: &str, formatted: &str) -> Option<usize> {
+    let original_lines: Vec<&str> = original.lines().collect();
+    let first = original_lines.first()?;
+
+    for start in cursor..lines.len() {
+        let line = &lines[start];
+        if !line.ends_with(first) || !line[..line.len() - first.len()].chars().all(|c| c == ' ') {
+            continue;
+        }
+        let indent = line[..line.len() - first.len()].to_string();
+        let matches = original_lines.iter().enumerate().all(|(offset, original_line)| {
+            match lines.get(start + offset) {
+                Some(line) if original_line.is_empty() => line.trim().is_empty(),
+                Some(line) => *line == format!("{}{}", indent, original_line),
+                None => false,
+            }
+        });
+        if matches {
+            let replacement: Vec<String> = formatted.lines().map(|line| format!("{}{}", indent, line)).collect();
+            let end = start + replacement.len();
+            lines.splice(start..start + original_lines.len(), replacement);
+            return Some(end);
+        }
+    }
+    None
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(signature: &str) -> String {
+        parse_signature(signature).expect("signature should parse").to_string()
+    }
+
+    #[test]
+    fn canonical_spacing_test() {
+        let data = "async   first<T,U>(\n    items:List[ T ],  # anything\n    limit :Int=10,\n)->  T?";
+        assert_eq!(format(data), "async first<T, U>(items: List[T], limit: Int = 10) -> T?")
+    }
+
+    #[test]
+    fn canonical_dependency_test() {
+        let data = "update_user(dynamo: {dynamodb.update_item}, http: {requests}) -> None";
+        assert_eq!(format(data), "update_user(dynamo: {dynamodb: update_item}, http: {requests}) -> None")
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let signatures = vec![
+            "get_users_for_account(http_client: {requests}, account_ids: List[AccountId]) -> Result[List[User], ErrorMsg]",
+            "#: Counts the users\ncount_users(users: List[models.User]) -> Int",
+            "pick(choice: (User | Admin)?, pair: (User,), empty: ()) -> ((User) -> Admin) | None",
+            "nested(handler: (Int) -> (Int) -> Int, members: User | (Admin | Guest)) -> Tuple[User, Admin]",
+            "set_status(status: Literal['active', \"disabled\", 3], wait: Bool = False) -> None",
+        ];
+        for signature in signatures {
+            let ast = parse_signature(signature).expect("signature should parse");
+            let formatted = ast.to_string();
+            assert_eq!(formatted, signature);
+            assert_eq!(parse_signature(&formatted), Ok(ast));
+        }
+    }
+
+    const SCHEMA: &str = r#"project_name: 'cleanup_account_users'
+root_directory: '/tmp/gen'
+language: 'python'
+system_types: 'system_types'
+domain_types: 'domain_types'
+files:
+    - path: 'src/domains/account'
+      methods:
+        # fetches every user
+        - 'get_users_for_account(http_client:{requests},account_ids: List[ AccountId ])->Result[List[User], ErrorMsg]'
+        - "set_status(status: Literal['active','disabled']) -> None"
+        - signature: 'convert_to_dto(users: List[User])  -> List[TableUsers]'
+          description: 'Maps users onto the table representation'
+        - |-
+          #: Counts the users
+          count_users(
+              users: List[User],
+          ) -> Int
+workflow:
+    - get_users_for_account
+"#;
+
+    #[test]
+    fn format_schema_source_test() {
+        let expected = r#"project_name: 'cleanup_account_users'
+root_directory: '/tmp/gen'
+language: 'python'
+system_types: 'system_types'
+domain_types: 'domain_types'
+files:
+    - path: 'src/domains/account'
+      methods:
+        # fetches every user
+        - 'get_users_for_account(http_client: {requests}, account_ids: List[AccountId]) -> Result[List[User], ErrorMsg]'
+        - "set_status(status: Literal['active', 'disabled']) -> None"
+        - signature: 'convert_to_dto(users: List[User]) -> List[TableUsers]'
+          description: 'Maps users onto the table representation'
+        - |-
+          #: Counts the users
+          count_users(users: List[User]) -> Int
+workflow:
+    - get_users_for_account
+"#;
+        let result = format_schema_source(SCHEMA).expect("schema should format");
+        assert_eq!(result, expected);
+        assert_eq!(format_schema_source(&result).expect("schema should format"), result);
+    }
+}
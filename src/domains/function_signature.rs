@@ -1,48 +1,76 @@
 use serde::{Deserialize, Serialize};
-use crate::language_interpreter::LanguageInterpreter;
-
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct ApplicationType {
     pub type_name: String,
-}
-
-impl LanguageInterpreter for ApplicationType {
-    fn as_python(&self) -> String {
-        self.type_name.clone()
-    }
-    fn as_javascript(&self) -> String {
-        self.type_name.clone()
-    }
+    pub module_path: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct ApplicationParentType {
     pub type_name: String,
+    pub module_path: Vec<String>,
     pub children: Vec<ParameterType>,
 }
 
-impl LanguageInterpreter for ApplicationParentType {
-    fn as_python(&self) -> String {
-        let children = self.children.iter().map(|child| child.as_python()).collect::<Vec<String>>().join(", ");
-        format!("{}[{}]", self.type_name, children)
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct OptionalType {
+    pub inner: Box<ParameterType>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct UnionType {
+    pub members: Vec<ParameterType>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct TupleType {
+    pub members: Vec<ParameterType>,
+}
+
+// `Literal['active', 'disabled']`, values are kept as written including their quotes
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct LiteralType {
+    pub values: Vec<String>,
+}
+
+impl LiteralType {
+    // every value is a number, otherwise they're all quoted strings
+    pub fn is_numeric(&self) -> bool {
+        self.values.iter().all(|value| value.parse::<f64>().is_ok())
     }
-    fn as_javascript(&self) -> String {
-        self.type_name.clone()
+
+    // a value of the right kind which is not one of the allowed values
+    pub fn invalid_value(&self) -> String {
+        if self.is_numeric() {
+            let largest = self.values.iter().flat_map(|value| value.parse::<f64>()).fold(f64::NEG_INFINITY, f64::max);
+            format!("{}", largest.floor() as i64 + 1)
+        } else {
+            String::from("'__invalid__'")
+        }
     }
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct FunctionType {
+    pub params: Vec<ParameterType>,
+    pub output: Box<ParameterType>,
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct Dependency {
     pub dependency_name: String,
+    pub methods: Vec<String>,
 }
 
-impl LanguageInterpreter for Dependency {
-    fn as_python(&self) -> String {
-        self.dependency_name.clone()
-    }
-    fn as_javascript(&self) -> String {
-        self.dependency_name.clone()
+impl Dependency {
+    // `dynamodb` is declared as `Dynamodb` wherever the dependency needs a type of its own
+    pub fn trait_name(&self) -> String {
+        let mut chars = self.dependency_name.chars();
+        match chars.next() {
+            None => String::new(),
+            Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        }
     }
 }
 
@@ -50,22 +78,28 @@ impl LanguageInterpreter for Dependency {
 pub enum ParameterType {
     ApplicationType(ApplicationType),
     ApplicationParentType(ApplicationParentType),
+    OptionalType(OptionalType),
+    UnionType(UnionType),
+    TupleType(TupleType),
+    Function(FunctionType),
+    LiteralType(LiteralType),
     Dependency(Dependency),
 }
 
-impl LanguageInterpreter for ParameterType {
-    fn as_python(&self) -> String {
-        match self {
-            Self::ApplicationType(value) => value.as_python(),
-            Self::ApplicationParentType(value) => value.as_python(),
-            Self::Dependency(value) => value.as_python(),
-        }
-    }
-    fn as_javascript(&self) -> String {
+impl ParameterType {
+    // depth-first visit of this type and every type nested inside it
+    pub fn walk<'a>(&'a self, visit: &mut dyn FnMut(&'a ParameterType)) {
+        visit(self);
         match self {
-            Self::ApplicationType(value) => value.as_javascript(),
-            Self::ApplicationParentType(value) => value.as_javascript(),
-            Self::Dependency(value) => value.as_javascript(),
+            Self::ApplicationParentType(value) => value.children.iter().for_each(|child| child.walk(visit)),
+            Self::OptionalType(value) => value.inner.walk(visit),
+            Self::UnionType(value) => value.members.iter().for_each(|member| member.walk(visit)),
+            Self::TupleType(value) => value.members.iter().for_each(|member| member.walk(visit)),
+            Self::Function(value) => {
+                value.params.iter().for_each(|param| param.walk(visit));
+                value.output.walk(visit);
+            },
+            Self::ApplicationType(_) | Self::LiteralType(_) | Self::Dependency(_) => {},
         }
     }
 }
@@ -74,15 +108,7 @@ impl LanguageInterpreter for ParameterType {
 pub struct FunctionParameter {
     pub name: String,
     pub ptype: ParameterType,
-}
-
-impl LanguageInterpreter for FunctionParameter {
-    fn as_python(&self) -> String {
-        format!("{}: {}", self.name, self.ptype.as_python())
-    }
-    fn as_javascript(&self) -> String {
-        format!("{}: {}", self.name, self.ptype.as_javascript())
-    }
+    pub default: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
@@ -90,20 +116,79 @@ pub struct FunctionSignature {
     pub name: String,
     pub input: Vec<FunctionParameter>,
     pub output: ParameterType,
+    pub is_async: bool,
+    pub type_parameters: Vec<String>,
+    pub description: Option<String>,
 }
 
-impl LanguageInterpreter for FunctionSignature {
-    fn as_python(&self) -> String {
-        let parameters = self.input.iter().map(|param| param.as_python()).collect::<Vec<String>>().join(", ");
-        format!("def {}({}) -> {}: \n    pass\n", self.name, parameters, self.output.as_python())
+impl FunctionSignature {
+    pub fn async_prefix(&self) -> &str {
+        if self.is_async { "async " } else { "" }
     }
-    fn as_javascript(&self) -> String {
-        format!("{}", self.name)
+
+    // comment lines placed above the function, e.g. " * " inside a jsdoc block
+    pub fn doc_comment(&self, prefix: &str) -> String {
+        match &self.description {
+            Some(description) => description.lines().map(|line| format!("{}{}\n", prefix, line)).collect(),
+            None => String::new(),
+        }
     }
-}
 
+    // `<T, U>` for languages which declare generics on the function itself
+    pub fn angle_type_parameters(&self) -> String {
+        if self.type_parameters.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", self.type_parameters.join(", "))
+        }
+    }
+}
 
 impl FunctionSignature {
+    // every parameter and output type, including the ones nested inside them
+    pub fn walk_types<'a>(&'a self, visit: &mut dyn FnMut(&'a ParameterType)) {
+        self.input.iter().for_each(|param| param.ptype.walk(visit));
+        self.output.walk(visit);
+    }
+
+    // the modules which qualify a type somewhere in this signature, e.g. ["boto3"] for boto3.Session
+    pub fn list_type_modules(&self) -> Vec<Vec<String>> {
+        let mut modules: Vec<Vec<String>> = vec![];
+        self.walk_types(&mut |ptype| {
+            let module_path = match ptype {
+                ParameterType::ApplicationType(value) => &value.module_path,
+                ParameterType::ApplicationParentType(value) => &value.module_path,
+                _ => return,
+            };
+            if !module_path.is_empty() && !modules.contains(module_path) {
+                modules.push(module_path.clone());
+            }
+        });
+        modules
+    }
+
+    // whether a type constructor such as `Result` appears anywhere in the signature
+    pub fn uses_type(&self, type_name: &str) -> bool {
+        let mut found = false;
+        self.walk_types(&mut |ptype| {
+            if let ParameterType::ApplicationParentType(value) = ptype {
+                found |= value.type_name == type_name && value.module_path.is_empty();
+            }
+        });
+        found
+    }
+
+    pub fn has_default_arguments(&self) -> bool {
+        self.input.iter().any(|param| param.default.is_some())
+    }
+
+    pub fn returns_result(&self) -> bool {
+        match &self.output {
+            ParameterType::ApplicationParentType(output) => output.type_name == "Result",
+            _ => false,
+        }
+    }
+
     pub fn list_dependencies(&self) -> Vec<&Dependency> {
         // return a list of all dependencies
         self.input.iter().flat_map(|param| {
@@ -120,53 +205,14 @@ mod tests {
     use super::*;
 
     #[test]
-    fn as_python_test() {
-        let ast = FunctionSignature { 
-            name: String::from("get_users_for_account"), 
-            input: vec![
-                FunctionParameter { 
-                    name: String::from("http_client"), 
-                    ptype: ParameterType::Dependency(
-                        Dependency { dependency_name: String::from("requests") }
-                    )
-                },
-                FunctionParameter {
-                    name: String::from("account_ids"), 
-                    ptype: ParameterType::ApplicationParentType(
-                        ApplicationParentType {
-                            type_name: String::from("List"),
-                            children: vec![
-                                ParameterType::ApplicationType(
-                                    ApplicationType { type_name: String::from("AccountId") }
-                                )
-                            ]
-                        }
-                    )
-                }
-            ], 
-            output: ParameterType::ApplicationParentType(
-                ApplicationParentType {
-                    type_name: String::from("Result"),
-                    children: vec![
-                        ParameterType::ApplicationParentType(
-                            ApplicationParentType {
-                                type_name: String::from("List"),
-                                children: vec![
-                                    ParameterType::ApplicationType(
-                                        ApplicationType { type_name: String::from("User") }
-                                    )
-                                ]
-                            }
-                        ),
-                        ParameterType::ApplicationType(
-                            ApplicationType { type_name: String::from("ErrorMsg") }
-                        )
-                    ]
-                }
-            )
-        };
-        let result = ast.as_python();
-        let expected = r#"def get_users_for_account(http_client: requests, account_ids: List[AccountId]) -> Result[List[User], ErrorMsg]:"#;
-        assert_eq!(result, expected)
+    fn negative_and_fractional_literal_test() {
+        let numeric = LiteralType { values: vec![String::from("1"), String::from("5")] };
+        assert_eq!(numeric.invalid_value(), "6");
+
+        let negative = LiteralType { values: vec![String::from("-5"), String::from("-3")] };
+        assert_eq!(negative.invalid_value(), "-2");
+
+        let fractional = LiteralType { values: vec![String::from("0.25"), String::from("1.5")] };
+        assert_eq!(fractional.invalid_value(), "2");
     }
 }
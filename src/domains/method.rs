@@ -1,6 +1,30 @@
 use serde::{Deserialize, Serialize};
 use crate::domains::function_signature::{ Dependency, FunctionSignature };
 
+// a `methods:` entry, either the bare signature or a mapping which also describes it
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(untagged)]
+pub enum UnprocessedMethod {
+    Signature(String),
+    Described { signature: String, description: String },
+}
+
+impl UnprocessedMethod {
+    pub fn signature(&self) -> &str {
+        match self {
+            Self::Signature(signature) => signature,
+            Self::Described { signature, .. } => signature,
+        }
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            Self::Signature(_) => None,
+            Self::Described { description, .. } => Some(description),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct ParsedMethod {
     pub raw: String,
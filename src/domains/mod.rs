@@ -2,4 +2,10 @@ pub mod schema;
 pub mod method;
 pub mod file;
 pub mod cli;
-pub mod function_signature;
\ No newline at end of file
+pub mod diagnostic;
+pub mod function_signature;
+pub mod type_definition;
+pub mod type_mapping;
+pub mod type_registry;
+pub mod workflow;
+pub mod formatter;
\ No newline at end of file
@@ -1,6 +1,5 @@
 use std::fs;
 use std::env;
-use std::borrow::Cow;
 use std::path::Path;
 use std::path::PathBuf;
 extern crate serde_yaml;
@@ -10,16 +9,24 @@ use exitfailure::ExitFailure;
 use tera::Tera;
 use tera::Context;
 
-use std::fs::File;
+use std::fs::{ File, OpenOptions };
 use std::io::prelude::*;
+use std::io::ErrorKind;
 
+use crate::backends::{ find_backend, Backend, Project, Workflow };
 use crate::domains::file::{ UnprocessedFile, ProcessedFile };
 use crate::domains::method::ParsedMethod;
-use crate::domains::function_signature::{FunctionSignature, Dependency};
-use crate::language_interpreter::{ LanguageInterpreter, LanguageInterpreterForUnitTest };
+use crate::domains::diagnostic::{
+    LanguageDiagnostic, LanguageDiagnostics, NameDiagnostics, SchemaDiagnostics, SignatureDiagnostic, TypeDiagnostics, WorkflowDiagnostics
+};
+use crate::domains::function_signature::Dependency;
+use crate::domains::type_definition::{ declaration_order, TypeDefinition };
+use crate::domains::type_mapping::TypeMappings;
+use crate::domains::type_registry::{ TypeRegistry, BUILT_IN_TYPES };
+use crate::domains::workflow::{ check_names, WorkflowChecker };
 
 use crate::signature_parser;
-use signature_parser::{ root };
+use signature_parser::{ clashing_variant_error, parse_signature, parse_type_definition };
 
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
@@ -29,17 +36,29 @@ pub struct Schema {
     language: String,
     system_types: std::path::PathBuf,
     domain_types: std::path::PathBuf,
+    // declarations such as `User { id: UserId }`, on top of any listed under domain_types
+    #[serde(default)]
+    types: Vec<String>,
+    // per language overrides of how built-in types such as `Result` are written
+    #[serde(default)]
+    type_mappings: TypeMappings,
     pub files: Vec<UnprocessedFile>,
     workflow: Vec<String>,
 }
 
+// a yaml file under the domain_types directory
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct TypeListing {
+    types: Vec<String>,
+}
+
 #[derive(Debug)]
 pub struct ParsedSchema {
     project_name: String,
     root_directory: String,
-    language: String,
-    system_types: std::path::PathBuf,
-    domain_types: std::path::PathBuf,
+    backend: &'static dyn Backend,
+    pub types: Vec<TypeDefinition>,
+    pub type_mappings: TypeMappings,
     pub files: Vec<ProcessedFile>,
     workflow: Vec<String>,
     pub templates: tera::Tera,
@@ -52,7 +71,7 @@ pub struct MockTest {
 }
 
 
-fn create_mock_name(s: &str) -> String {
+pub fn create_mock_name(s: &str) -> String {
     let mut c = s.chars();
     match c.next() {
         None => String::new(),
@@ -60,20 +79,8 @@ fn create_mock_name(s: &str) -> String {
     }
 }
 
-fn get_dot_separated_path(path: &PathBuf) -> String {
-    let path_string = path.as_path().display().to_string();
-    path_string.split("/").collect::<Vec<&str>>().join(".")
-}
-
-impl LanguageInterpreterForUnitTest for MockTest {
-    fn as_python(&self, mock_ref: &str, mock_config: &MockConfig, target_ref: &str) -> String {
-        let snake_case_description = self.description.replace(" ", "_");    
-        format!("def test_{}():\n    {} = MagicMock({})\n    result = {}({})\n    assert 1 == 2", 
-            snake_case_description, mock_ref, self.mock_response, target_ref, create_mock_name(&mock_config.name))
-    }
-    fn as_javascript(&self, mock_ref: &str, mock_config: &MockConfig, target_ref: &str) -> String {
-        format!("it('{}', () => {{}}))", self.description)
-    }
+fn is_yaml(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "yaml" || extension == "yml")
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
@@ -81,6 +88,9 @@ pub struct MockConfig {
     pub name: String,
     pub import_statement: String,
     pub imports: Vec<String>,
+    // methods patched when a signature doesn't say which ones it calls
+    #[serde(default)]
+    pub methods: Vec<String>,
     pub mock: String,
     pub tests: Vec<MockTest>,
 }
@@ -98,7 +108,7 @@ pub struct MethodAndMocks {
 // TODO: provide functionality for:
 // [√] creating files
 // [√] creating functions
-//     [ ] check types exist
+//     [√] check types exist
 // [ ] partially apply dependencies in the main file
 // [ ] creating unit tests
 
@@ -106,11 +116,61 @@ pub struct MethodAndMocks {
 // Basic Schema which can return a ParsedSchema
 impl Schema {
     pub fn process_schema(&self) -> Result<ParsedSchema, ExitFailure> {
-        let processed_files = self.files.iter().map(|file| 
-            ProcessedFile::new(file.path.clone(), Self::create_ast(&file))
-        ).collect::<Vec<_>>();
+        let backend = find_backend(&self.language)?;
+        let mut processed_files = vec![];
+        let mut diagnostics = vec![];
+        let system_types = self.list_system_types()?;
+        let types = match Self::create_type_definitions(&self.list_type_sources()?, &system_types) {
+            Ok(types) => types,
+            Err(mut type_diagnostics) => {
+                diagnostics.append(&mut type_diagnostics);
+                vec![]
+            },
+        };
+        for file in &self.files {
+            match Self::create_ast(file) {
+                Ok(methods) => processed_files.push(ProcessedFile::new(file.path.clone(), methods)),
+                Err(mut file_diagnostics) => diagnostics.append(&mut file_diagnostics),
+            }
+        }
+        if !diagnostics.is_empty() {
+            return Err(SchemaDiagnostics { diagnostics }.into());
+        }
 
-        let project_path = std::path::PathBuf::from("./project_repository/templates/python38");
+        let naming_problems = check_names(&self.workflow, &processed_files);
+        if !naming_problems.is_empty() {
+            return Err(NameDiagnostics { diagnostics: naming_problems }.into());
+        }
+
+        // a constructor given a mapping is as good as a built-in
+        let mut known_types = system_types;
+        known_types.extend(self.type_mappings.type_names());
+        let registry = TypeRegistry::new(&types, &known_types);
+        let unknown_types = registry.check_files(&processed_files);
+        if !unknown_types.is_empty() {
+            return Err(TypeDiagnostics { diagnostics: unknown_types }.into());
+        }
+
+        let type_mappings = TypeMappings::standard().with_overrides(&self.type_mappings);
+        let (mappings, language) = (&type_mappings, backend.language());
+        let unsupported: Vec<LanguageDiagnostic> = processed_files.iter().flat_map(|file| file.methods.iter().flat_map(move |method| {
+            mappings.check_signature(language, &method.ast).map(|message| LanguageDiagnostic {
+                path: file.path.clone(),
+                method_name: method.ast.name.clone(),
+                language: language.to_string(),
+                message,
+            })
+        })).collect();
+        if !unsupported.is_empty() {
+            return Err(LanguageDiagnostics { diagnostics: unsupported }.into());
+        }
+
+        let mismatches = WorkflowChecker::new(&types).check(&self.workflow, &processed_files);
+        if !mismatches.is_empty() {
+            return Err(WorkflowDiagnostics { diagnostics: mismatches }.into());
+        }
+
+        let project_path = PathBuf::from("./project_repository/templates").join(backend.template_set());
         let full_path = fs::canonicalize(&project_path)?;
         let template_path = full_path.as_path().display().to_string() + "/**/*.hbs";
 
@@ -128,9 +188,9 @@ impl Schema {
             ParsedSchema {
                 project_name: self.project_name.clone(),
                 root_directory: self.root_directory.clone(),
-                language: self.language.clone(),
-                system_types: self.system_types.clone(),
-                domain_types: self.domain_types.clone(),
+                backend,
+                types,
+                type_mappings,
                 files: processed_files,
                 workflow: self.workflow.clone(),
                 templates: tera,
@@ -138,11 +198,94 @@ impl Schema {
         )
     }
 
-    fn create_ast(file: &UnprocessedFile) -> Vec<ParsedMethod> {
-        file.methods.iter().map(|method| {
-            let (_, ast) = root(method.as_str()).unwrap();
-            ParsedMethod { raw: method.to_string(), ast }
-        }).collect()
+    // the schema's own `types:` followed by each listing in the domain_types directory, if there is one
+    fn list_type_sources(&self) -> Result<Vec<(PathBuf, Vec<String>)>, ExitFailure> {
+        let mut sources = vec![(PathBuf::new(), self.types.clone())];
+        if self.domain_types.is_dir() {
+            let mut listing_paths: Vec<PathBuf> = fs::read_dir(&self.domain_types)?
+                .flat_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| is_yaml(path))
+                .collect();
+            listing_paths.sort();
+            for path in listing_paths {
+                let listing: TypeListing = serde_yaml::from_reader(File::open(&path)?)?;
+                sources.push((path, listing.types));
+            }
+        }
+        Ok(sources)
+    }
+
+    // system types already exist in the target platform, their listings only need to name them
+    fn list_system_types(&self) -> Result<Vec<String>, ExitFailure> {
+        let mut names = vec![];
+        if self.system_types.is_dir() {
+            for entry in fs::read_dir(&self.system_types)? {
+                let path = entry?.path();
+                if !is_yaml(&path) {
+                    continue;
+                }
+                let listing: TypeListing = serde_yaml::from_reader(File::open(&path)?)?;
+                names.extend(listing.types.iter().map(|entry| {
+                    entry.trim().chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect::<String>()
+                }));
+            }
+        }
+        Ok(names)
+    }
+
+    fn create_type_definitions(sources: &[(PathBuf, Vec<String>)], system_types: &[String]) -> Result<Vec<TypeDefinition>, Vec<SignatureDiagnostic>> {
+        let mut parsed = vec![];
+        let mut diagnostics = vec![];
+        for (path, definitions) in sources {
+            for (index, definition) in definitions.iter().enumerate() {
+                match parse_type_definition(definition) {
+                    Ok(type_definition) => parsed.push((path, index, definition, type_definition)),
+                    Err(error) => diagnostics.push(SignatureDiagnostic::for_type(path.clone(), index, definition, error)),
+                }
+            }
+        }
+
+        // only once every type is known can a sum of them be told apart from a redeclaration,
+        // built-ins and system types exist already so can't be redeclared as variants either
+        let mut existing: Vec<String> = parsed.iter().map(|(_, _, _, type_definition)| type_definition.name().to_string()).collect();
+        existing.extend(BUILT_IN_TYPES.iter().map(|name| name.to_string()));
+        existing.extend(system_types.iter().cloned());
+        let mut types = vec![];
+        for (path, index, definition, type_definition) in parsed {
+            match type_definition.resolve_union(&existing) {
+                Ok(type_definition) => types.push(type_definition),
+                Err(variant) => diagnostics.push(SignatureDiagnostic::for_type(path.clone(), index, definition, clashing_variant_error(definition, &variant))),
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(types)
+        } else {
+            Err(diagnostics)
+        }
+    }
+
+    fn create_ast(file: &UnprocessedFile) -> Result<Vec<ParsedMethod>, Vec<SignatureDiagnostic>> {
+        let mut methods = vec![];
+        let mut diagnostics = vec![];
+        for (index, method) in file.methods.iter().enumerate() {
+            match parse_signature(method.signature()) {
+                Ok(mut ast) => {
+                    // a description given alongside the signature wins over a `#:` doc comment
+                    if let Some(description) = method.description() {
+                        ast.description = Some(description.to_string());
+                    }
+                    methods.push(ParsedMethod { raw: method.signature().to_string(), ast })
+                },
+                Err(error) => diagnostics.push(SignatureDiagnostic::new(file.path.clone(), index, method.signature(), error)),
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(methods)
+        } else {
+            Err(diagnostics)
+        }
     }
 }
 
@@ -150,7 +293,7 @@ impl Schema {
 impl ParsedSchema {
     pub fn generate(&self) -> Result<(), ExitFailure> {
         println!("about to read mock file");
-        let mock_listing_path = std::path::PathBuf::from("./project_repository/mocks/python/mock_listings.yaml");
+        let mock_listing_path = PathBuf::from("./project_repository/mocks").join(self.backend.mock_listing_set()).join("mock_listings.yaml");
         let mock_listing_file_handler = std::fs::File::open(mock_listing_path)?;
         let mock_listings: MockListing = serde_yaml::from_reader(mock_listing_file_handler)?;
         
@@ -160,11 +303,14 @@ impl ParsedSchema {
                 println!("{}: {:?}", key, val);
                 println!("Application root dir: {:?}\n", self.root_directory);
 
-                self.create_main_file(&mock_listings);
-                self.create_application_files();
-                self.create_mocks_file(&mock_listings); // TODO: this needs to return a list of mocks to be included in a mocks files
-                self.create_test_files(&mock_listings);
-                self.create_main_test_file(&mock_listings);
+                self.create_main_file(&mock_listings)?;
+                self.create_domain_types_file()?;
+                self.create_dependencies_file(&mock_listings)?;
+                self.create_application_files()?;
+                self.create_mocks_file(&mock_listings)?; // TODO: this needs to return a list of mocks to be included in a mocks files
+                self.create_test_files(&mock_listings)?;
+                self.create_main_test_file()?;
+                self.create_project_files()?;
             },
             None => println!("{} is not defined in the environment.", key),
         }
@@ -173,101 +319,172 @@ impl ParsedSchema {
     }
 
 
+    fn project(&self) -> Project<'_> {
+        Project {
+            name: &self.project_name,
+            root_directory: &self.root_directory,
+            types: &self.types,
+            mappings: &self.type_mappings,
+        }
+    }
+
     // get consolidated list of this schema's dependencies
     fn list_dependencies(&self) -> Vec<&Dependency> {
         self.files.iter().flat_map(|file| file.list_dependencies()).collect()
     }
 
+    // every dependency once, with all the methods any signature calls on it, or the mock listing's when none say
+    fn dependency_methods(&self, mock_listings: &MockListing) -> Vec<(Dependency, Vec<String>)> {
+        let mut merged: Vec<(Dependency, Vec<String>)> = vec![];
+        for dependency in self.list_dependencies() {
+            match merged.iter_mut().find(|(known, _)| known.dependency_name == dependency.dependency_name) {
+                Some((_, methods)) => methods.extend(dependency.methods.iter().filter(|method| !methods.contains(method)).cloned().collect::<Vec<String>>()),
+                None => merged.push((dependency.clone(), dependency.methods.clone())),
+            }
+        }
+        merged.sort_by(|(a, _), (b, _)| a.dependency_name.cmp(&b.dependency_name));
+        for (dependency, methods) in merged.iter_mut() {
+            if methods.is_empty() {
+                if let Some(config) = mock_listings.mocks.iter().find(|mock| mock.name == dependency.dependency_name) {
+                    methods.extend(config.methods.iter().cloned());
+                }
+            }
+        }
+        merged
+    }
+
+
+    fn crate_name(&self) -> String {
+        self.project().crate_name()
+    }
+
+    // the modules declared in lib.rs, nested ones are declared by their directory's mod.rs
+    fn top_level_modules(&self) -> Vec<String> {
+        let mut modules: Vec<String> = vec![];
+        if !self.types.is_empty() {
+            modules.push(String::from("domain_types"));
+        }
+        if !self.list_dependencies().is_empty() {
+            modules.push(String::from("dependencies"));
+        }
+        for file in &self.files {
+            let module = file.path.strip_prefix("src").ok()
+                .and_then(|path| path.components().next())
+                .map(|component| component.as_os_str().to_string_lossy().to_string());
+            if let Some(module) = module {
+                if !modules.contains(&module) {
+                    modules.push(module);
+                }
+            }
+        }
+        modules
+    }
+
 
     pub fn create_mocks_file(&self, mock_listings: &MockListing) -> Result<(), ExitFailure> {
         let mut dependencies: Vec<&Dependency> = self.list_dependencies();
         dependencies.sort_by(|a, b| b.dependency_name.cmp(&a.dependency_name));
-        dependencies.dedup();
+        dependencies.dedup_by(|a, b| a.dependency_name == b.dependency_name);
 
         let mock_configs: Vec<&MockConfig> = dependencies.into_iter().flat_map(|dependency| {
             mock_listings.mocks.iter().find(|mock| mock.name == dependency.dependency_name)
         }).collect();
 
-        let mock_list: Vec<String> = mock_configs.iter().map(|config| config.mock.clone()).collect();
+        let mock_list = self.backend.mock_list(&mock_configs, &self.dependency_methods(mock_listings));
+        let mock_names: Vec<String> = mock_configs.iter().map(|config| create_mock_name(&config.name)).collect();
 
         let mut context = Context::new();
         context.insert("mock_list", &mock_list);
+        context.insert("mock_names", &mock_names);
+        context.insert("crate_name", &self.crate_name());
         let output = self.templates.render("test_mocks.hbs", &context)?;
-
-        self.write_to_file(&output, "tests/mocks.py");
+        self.write_to_file(&output, &self.backend.mocks_file())?;
 
         Ok(())
     }
 
 
-    fn write_to_file(&self, content: &String, filename: &str) {
+    fn write_to_file(&self, content: &str, filename: &str) -> Result<(), ExitFailure> {
         let path_str = format!("{}/{}", self.root_directory, filename);
         let path = Path::new(&path_str);
         let display = path.display();
+        let couldnt = |action: &str, path: &Path, why: std::io::Error| failure::err_msg(format!("couldn't {} {}: {}", action, path.display(), why));
 
         let prefix = path.parent().unwrap();
-        std::fs::create_dir_all(prefix).unwrap();
-
-        let module_file = match self.language.as_str() {
-            "python" => "__init_.py",
-            "rust" => "mod.rs",
-            _ => "unknown_language",
-        }; 
-            
-        let parent = path.parent().unwrap();
-        let file_path = PathBuf::from(parent).join(module_file);
-        let init_file_exists = file_path.exists();
-        if init_file_exists == false {
-            match File::create(&file_path) {
-                Err(why) => panic!("couldn't create {}: {}", display, why),
-                Ok(file) => file,
+        std::fs::create_dir_all(prefix).map_err(|why| couldnt("create", prefix, why))?;
+
+        // the files which make the new one part of a module, each missing line is added to them
+        for (declaring_file, line) in self.backend.module_declarations(Path::new(filename)) {
+            let declaring_path = Path::new(&self.root_directory).join(&declaring_file);
+            let existing = match fs::read_to_string(&declaring_path) {
+                Ok(existing) => Some(existing),
+                Err(why) if why.kind() == ErrorKind::NotFound => None,
+                Err(why) => return Err(couldnt("read", &declaring_path, why).into()),
             };
+            let missing = match &existing {
+                None => true,
+                Some(existing) => !line.is_empty() && !existing.lines().any(|existing_line| existing_line == line),
+            };
+            if missing {
+                let mut declaring = OpenOptions::new().create(true).append(true).open(&declaring_path)
+                    .map_err(|why| couldnt("create", &declaring_path, why))?;
+                if !line.is_empty() {
+                    writeln!(declaring, "{}", line).map_err(|why| couldnt("write to", &declaring_path, why))?;
+                }
+            }
         }
 
-        let mut file = match File::create(&path) {
-            Err(why) => panic!("couldn't create {}: {}", display, why),
-            Ok(file) => file,
-        };
+        let mut file = File::create(path).map_err(|why| couldnt("create", path, why))?;
+        file.write_all(content.as_bytes()).map_err(|why| couldnt("write to", path, why))?;
+        println!("successfully wrote to {}", display);
+        Ok(())
+    }
 
-        match file.write_all(content.as_bytes()) {
-            Err(why) => panic!("couldn't write to {}: {}", display, why),
-            Ok(_) => println!("successfully wrote to {}", display),
+    // every declared type in one module which the application files import
+    fn create_domain_types_file(&self) -> Result<(), ExitFailure> {
+        if self.types.is_empty() {
+            return Ok(());
         }
+
+        let definitions = declaration_order(&self.types).iter()
+            .map(|definition| self.backend.render_type_definition(definition, &self.type_mappings))
+            .collect::<Vec<String>>();
+        self.write_to_file(&self.backend.domain_types_module(&definitions), &self.backend.domain_types_file())
     }
 
+
+    // a protocol or typedef per dependency, naming the methods the application calls on it
+    fn create_dependencies_file(&self, mock_listings: &MockListing) -> Result<(), ExitFailure> {
+        let dependencies = self.dependency_methods(mock_listings);
+        if dependencies.is_empty() {
+            return Ok(());
+        }
+
+        let mut context = Context::new();
+        context.insert("declarations", &self.backend.dependency_declarations(&dependencies));
+        let output = self.templates.render("dependencies.hbs", &context)?;
+        self.write_to_file(&output, &self.backend.dependencies_file())?;
+
+        Ok(())
+    }
+
+
     fn create_application_files(&self) -> Result<(), ExitFailure> {
+        let project = self.project();
         for file in &self.files {
-            let functions = file.methods.iter().map(|method| method.ast.as_python()).collect::<Vec<String>>().join("\n");
+            let content = self.backend.application_module(&project, file);
 
             // TODO: 
             // [ ] the layout should be dictated by the handlebars template like with the mocks
-            // [ ] Types need to be imported with each file
-            let path = file.path.as_path().display().to_string();
-            let full_path = match self.language.as_str() {
-                "python" => format!("{}.py", path),
-                "javascript" => format!("{}.js", path),
-                _ => format!("{}", path),
-            };
-            self.write_to_file(&functions, full_path.as_str());
+            // [√] Types need to be imported with each file
+            let full_path = format!("{}.{}", file.path.as_path().display(), self.backend.file_extension());
+            self.write_to_file(&content, full_path.as_str())?;
         }
 
         Ok(())
     }
 
 
-    fn build_tests_for_methods(&self, method_and_mock: &MethodAndMocks, is_main: bool) -> Vec<String> {
-        method_and_mock.mocks.iter().flat_map(|config| {
-            let mock_ref: Cow<str> = if is_main {
-                "index.requests.get".into() // EXAMPLE
-            } else {
-                let mock_name = create_mock_name(config.name.as_str());
-                format!("{}.get", &mock_name).into()
-            };
-            let method_name = method_and_mock.method.ast.name.as_str();
-            config.tests.iter().map(|test| test.as_python(&mock_ref, config, method_name)).collect::<Vec<String>>()
-        }).collect()
-    }
-
     fn retrieve_mock_configs(&self, method: &ParsedMethod, mock_listings: &MockListing) -> MethodAndMocks {
         let dependencies: Vec<String> = method.list_dependencies().iter().map(|dep| dep.dependency_name.clone()).collect();
 
@@ -284,51 +501,15 @@ impl ParsedSchema {
 
 
     fn create_test_files(&self, mock_listings: &MockListing) -> Result<(), ExitFailure> {
+        let project = self.project();
         for file in &self.files {
             let is_main = file.path.ends_with("main") || file.path.ends_with("index");
 
-            let methods_and_mocks: Vec<MethodAndMocks> = file.methods.iter().map(|method| self.retrieve_mock_configs(&method, mock_listings)).collect();
-            let tests: Vec<String> = methods_and_mocks.iter().flat_map(|method_and_mocks| self.build_tests_for_methods(&method_and_mocks, is_main)).collect();
-
-            let combined_file_dependencies = file.list_dependencies();
-
-            let mut imports: Vec<String> = methods_and_mocks.iter().map(|mams| {
-                mams.mocks.iter().flat_map(|mock| mock.imports.clone()).collect()
-            }).collect();
-            // TODO: it should import RequestsMock not requests?
-            let joined_dependencies: String = combined_file_dependencies.iter().map(|dep| {
-                create_mock_name(dep.dependency_name.clone().as_str())
-            }).collect::<Vec<String>>().join(", ");
-            // TODO: this is python specific, generalise.
-            imports.push(format!("from tests.mocks import {}", joined_dependencies));
-
-            let path = file.path.as_path().display().to_string();
-            let test_file_path = path.replace("src", "tests");
-            let full_path = format!("{}.{}", test_file_path, self.get_file_type());
-            
-            let python_sys_path_assignment = format!(r#"BASE_DIR = os.path.dirname(os.path.abspath(__file__))
-sys.path.append(BASE_DIR)
-sys.path.insert(0, os.path.join(BASE_DIR, "../{}"))"#, path);
-            let sys_path_assignment = if self.language == "python" {
-                python_sys_path_assignment.as_str()
-            } else {
-                ""
-            };
-            
-            // from src.domains.user import User, PersistedUser  # noqa
-            let dot_separated_path: String = path.split("/").collect::<Vec<&str>>().join(".");
-            let method_names: String = file.methods.iter().map(|method| method.ast.name.clone()).collect::<Vec<String>>().join(", ");
-
-            let local_imports: Vec<String> = vec![format!("from {} import {}", dot_separated_path, method_names)];
-
-            let mut context = Context::new();
-            context.insert("imports", &imports);
-            context.insert("local_imports", &local_imports);
-            context.insert("sys_path_assignment", sys_path_assignment);
-            context.insert("tests", &tests);
-            let output = self.templates.render("test.hbs", &context)?;
-            
-            self.write_to_file(&output, full_path.as_str());
+            let methods_and_mocks: Vec<MethodAndMocks> = file.methods.iter().map(|method| self.retrieve_mock_configs(method, mock_listings)).collect();
+            if let Some(test_file) = self.backend.test_module(&project, file, &methods_and_mocks, is_main) {
+                let output = self.templates.render("test.hbs", &test_file.context)?;
+                self.write_to_file(&output, &test_file.path)?;
+            }
         }
 
         // create separate test_main.py which requires all mocks
@@ -336,85 +517,75 @@ sys.path.insert(0, os.path.join(BASE_DIR, "../{}"))"#, path);
     }
 
 
-    fn get_file_type(&self) -> &str {
-        match self.language.as_str() {
-            "python" => "py",
-            "javascript" => "js",
-            _ => "",
+    fn create_main_test_file(&self) -> Result<(), ExitFailure> {
+        let mut mock_names: Vec<String> = self.list_dependencies().iter().map(|dep| {
+            create_mock_name(dep.dependency_name.clone().as_str())
+        }).collect();
+        mock_names.sort();
+        mock_names.dedup();
+
+        if let Some(test_file) = self.backend.main_test(&self.project(), &mock_names) {
+            let output = self.templates.render("test.hbs", &test_file.context)?;
+            self.write_to_file(&output, &test_file.path)?;
         }
+
+        Ok(())
     }
 
 
-    fn create_main_test_file(&self, mock_listings: &MockListing) -> Result<(), ExitFailure> {
-        let dependencies = self.list_dependencies();
-        let joined_dependencies: String = dependencies.iter().map(|dep| {
-            create_mock_name(dep.dependency_name.clone().as_str())
-        }).collect::<Vec<String>>().join(", ");
+    fn create_main_file(&self, mock_listings: &MockListing) -> Result<(), ExitFailure> {
+        let steps: Vec<(&ProcessedFile, &ParsedMethod)> = self.workflow.iter().flat_map(|step| {
+            self.files.iter().flat_map(|file| file.methods.iter().map(move |method| (file, method))).find(|(_, method)| &method.ast.name == step)
+        }).collect();
+        let async_steps: Vec<&String> = steps.iter().filter(|(_, method)| method.ast.is_async).map(|(_, method)| &method.ast.name).collect();
+        // the steps whose Ok value is handed on, rather than what they return
+        let result_steps: Vec<&String> = steps.iter().filter(|(_, method)| method.ast.returns_result()).map(|(_, method)| &method.ast.name).collect();
+        let workflow_is_async = !async_steps.is_empty();
+
+        let workflow = Workflow { files: &self.files, steps, mocks: &mock_listings.mocks };
+        let mut context = self.backend.main_context(&self.project(), &workflow);
+        context.insert("async_steps", &async_steps);
+        context.insert("result_steps", &result_steps);
+        context.insert("workflow_is_async", &workflow_is_async);
+        context.insert("workflow", &self.workflow);
 
-        let imports = vec![format!("from tests.mocks import {}", joined_dependencies)];
+        let output = self.templates.render("main.hbs", &context)?;
+        self.write_to_file(&output, self.backend.main_file())?;
 
-        let sys_path_assignment = format!(r#"BASE_DIR = os.path.dirname(os.path.abspath(__file__))
-sys.path.append(BASE_DIR)
-sys.path.insert(0, os.path.join(BASE_DIR, "../{}"))"#, self.root_directory);
+        Ok(())
+    }
 
-        let tests: Vec<String> = vec![];
-        let local_imports: Vec<String> = vec![String::from("import main")];
 
+    // manifests such as package.json with the runtime libraries the dependencies are built from
+    fn create_project_files(&self) -> Result<(), ExitFailure> {
         let mut context = Context::new();
-        context.insert("imports", &imports);
-        context.insert("local_imports", &local_imports);
-        context.insert("sys_path_assignment", &sys_path_assignment);
-        context.insert("tests", &tests);
-        let output = self.templates.render("test.hbs", &context)?;
-        
-        self.write_to_file(&output, format!("tests/test_main.py").as_str());
+        context.insert("project_name", &self.project_name);
+        context.insert("crate_name", &self.crate_name());
+        context.insert("modules", &self.top_level_modules());
+        for (template, path) in self.backend.project_files() {
+            let output = self.templates.render(template, &context)?;
+            self.write_to_file(&output, path)?;
+        }
 
         Ok(())
     }
+}
 
 
-    fn create_main_file(&self, mock_listings: &MockListing) -> Result<(), ExitFailure> {
-        let application_files: Vec<String> = self.files.iter().map(|file| {
-            let dot_separated_path = get_dot_separated_path(&file.path);
-            let method_names: String = file.methods.iter().map(|method| method.ast.name.clone()).collect::<Vec<String>>().join(", ");
-            format!("from {} import {}", dot_separated_path, method_names)
-        }).collect();
-
-        let mut dependencies: Vec<String> = self.list_dependencies().iter().map(|dep| dep.dependency_name.clone()).collect();
-        dependencies.sort();
-        dependencies.dedup();
-        let mock_configs: Vec<&MockConfig> = dependencies.into_iter().flat_map(|dependency| {
-            mock_listings.mocks.iter().find(|mock| mock.name == dependency)
-        }).collect();
-        let import_statements: Vec<String> = mock_configs.iter().map(|config| config.import_statement.clone()).collect();
-
-        let functions_with_side_effects: Vec<String> = self.files.iter().map(|file| {
-            file.methods.iter().map(|method| {
-                let dependency_names: String = method.list_dependencies().iter().map(|dep| dep.dependency_name.clone()).collect::<Vec<String>>().join(", ");
-                if dependency_names.len() > 0 {
-                    format!("{} = partial({}, {})\n", method.ast.name, method.ast.name, dependency_names)
-                } else {
-                    String::from("")
-                }
-            }).collect()
-        }).collect();
-
-        println!("functions_with_side_effects: {:?}", functions_with_side_effects);
-        let mut context = Context::new();
-        context.insert("application_files", &application_files);
-        context.insert("dependencies", &import_statements);
-        context.insert("functions_with_side_effects", &functions_with_side_effects);
-        context.insert("workflow", &self.workflow);
-
-        let output = self.templates.render("main.hbs", &context)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::function_signature::ParameterType;
 
-        let full_path = match self.language.as_str() {
-            "python" => "src/main.py",
-            "javascript" => "src/index.js",
-            _ => "unknown_language",
-        };
-        self.write_to_file(&output, full_path);
+    #[test]
+    fn built_in_and_system_variants_test() {
+        let sources = vec![(PathBuf::new(), vec![String::from("Key = String | Int"), String::from("Handle = Session | Local { path: String }")])];
+        let types = Schema::create_type_definitions(&sources, &[]).unwrap();
+        assert!(matches!(&types[0], TypeDefinition::Alias(alias) if matches!(alias.target, ParameterType::UnionType(_))));
+        assert!(matches!(&types[1], TypeDefinition::Sum(_)));
 
-        Ok(())
+        let diagnostics = Schema::create_type_definitions(&sources, &[String::from("Session")]).unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].index, diagnostics[0].error.column), (1, 10));
     }
 }
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use crate::domains::function_signature::{ ApplicationType, ParameterType, UnionType };
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct TypeField {
+    pub name: String,
+    pub ftype: ParameterType,
+}
+
+// `User { id: UserId, email: Email }`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct RecordType {
+    pub name: String,
+    pub fields: Vec<TypeField>,
+}
+
+// `Status = Active | Disabled { reason: String }`, each variant is a record of its own
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SumType {
+    pub name: String,
+    pub variants: Vec<RecordType>,
+}
+
+// `UserId = String`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct AliasType {
+    pub name: String,
+    pub target: ParameterType,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum TypeDefinition {
+    Record(RecordType),
+    Sum(SumType),
+    Alias(AliasType),
+}
+
+impl TypeDefinition {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Record(value) => &value.name,
+            Self::Sum(value) => &value.name,
+            Self::Alias(value) => &value.name,
+        }
+    }
+
+    // `Principal = User | Admin` parses as a sum type, but when every variant names a type which
+    // already exists it's a union of those types instead. A variant which names an existing type
+    // next to new ones would redeclare it, so that variant is given back as the error.
+    pub fn resolve_union(self, existing: &[String]) -> Result<TypeDefinition, String> {
+        let sum = match self {
+            Self::Sum(sum) => sum,
+            definition => return Ok(definition),
+        };
+        let exists = |variant: &RecordType| existing.contains(&variant.name);
+        if sum.variants.iter().all(|variant| variant.fields.is_empty() && exists(variant)) {
+            let members = sum.variants.into_iter()
+                .map(|variant| ParameterType::ApplicationType(ApplicationType { type_name: variant.name, module_path: vec![] }))
+                .collect();
+            return Ok(Self::Alias(AliasType { name: sum.name, target: ParameterType::UnionType(UnionType { members }) }));
+        }
+        match sum.variants.iter().find(|variant| exists(variant)) {
+            Some(clashing) => Err(clashing.name.clone()),
+            None => Ok(Self::Sum(sum)),
+        }
+    }
+}
+
+// each alias after the declared types it names, python evaluates an alias as the module loads
+pub fn declaration_order(types: &[TypeDefinition]) -> Vec<&TypeDefinition> {
+    fn visit<'a>(definition: &'a TypeDefinition, types: &'a [TypeDefinition], ordered: &mut Vec<&'a TypeDefinition>, visiting: &mut Vec<&'a str>) {
+        if ordered.contains(&definition) || visiting.contains(&definition.name()) {
+            return;
+        }
+        visiting.push(definition.name());
+        if let TypeDefinition::Alias(alias) = definition {
+            alias.target.walk(&mut |ptype| {
+                let type_name = match ptype {
+                    ParameterType::ApplicationType(value) => &value.type_name,
+                    ParameterType::ApplicationParentType(value) => &value.type_name,
+                    _ => return,
+                };
+                if let Some(named) = types.iter().find(|other| other.name() == type_name) {
+                    visit(named, types, ordered, visiting);
+                }
+            });
+        }
+        ordered.push(definition);
+    }
+
+    let mut ordered = vec![];
+    for definition in types {
+        visit(definition, types, &mut ordered, &mut vec![]);
+    }
+    ordered
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature_parser::parse_type_definition;
+
+    fn parse_all(definitions: &[&str]) -> Vec<TypeDefinition> {
+        definitions.iter().map(|definition| parse_type_definition(definition).unwrap()).collect()
+    }
+
+    #[test]
+    fn resolve_union_test() {
+        let existing = vec![String::from("User"), String::from("Admin"), String::from("AccountId")];
+        let union = |names: &[&str]| ParameterType::UnionType(UnionType {
+            members: names.iter().map(|name| ParameterType::ApplicationType(ApplicationType { type_name: name.to_string(), module_path: vec![] })).collect(),
+        });
+        let declared = parse_all(&["Principal = User | Admin", "Status = Active | Disabled", "Visitor = User | Guest { token: String }"]);
+        let mut resolved = declared.into_iter().map(|definition| definition.resolve_union(&existing));
+
+        let principal = TypeDefinition::Alias(AliasType { name: String::from("Principal"), target: union(&["User", "Admin"]) });
+        assert_eq!(resolved.next(), Some(Ok(principal)));
+        assert!(matches!(resolved.next(), Some(Ok(TypeDefinition::Sum(_)))));
+        assert_eq!(resolved.next(), Some(Err(String::from("User"))));
+    }
+
+    #[test]
+    fn declaration_order_test() {
+        let existing = vec![String::from("User"), String::from("Admin")];
+        let types: Vec<TypeDefinition> = parse_all(&["Principal = User | Admin", "Users = List[User]", "User { id: UserId }", "Admin { id: UserId }", "UserId = String"])
+            .into_iter().map(|definition| definition.resolve_union(&existing).unwrap()).collect();
+        let names: Vec<&str> = declaration_order(&types).iter().map(|definition| definition.name()).collect();
+        assert_eq!(names, vec!["User", "Admin", "Principal", "Users", "UserId"])
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::domains::function_signature::{ FunctionSignature, ParameterType };
+
+
+// How each target language spells the built-in type constructors. Templates refer to the
+// constructor's type arguments as `{0}`, `{1}`... or to all of them, comma separated, as `{..}`.
+// A schema's `type_mappings:` section overrides individual entries, e.g. `rust: { Result: 'anyhow::Result<{0}>' }`.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct TypeMappings {
+    #[serde(default)]
+    pub python: BTreeMap<String, String>,
+    #[serde(default)]
+    pub javascript: BTreeMap<String, String>,
+    #[serde(default)]
+    pub typescript: BTreeMap<String, String>,
+    #[serde(default)]
+    pub rust: BTreeMap<String, String>,
+    #[serde(default)]
+    pub go: BTreeMap<String, String>,
+}
+
+fn table(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+    entries.iter().map(|(name, template)| (name.to_string(), template.to_string())).collect()
+}
+
+impl TypeMappings {
+    pub fn standard() -> TypeMappings {
+        TypeMappings {
+            python: table(&[
+                ("Any", "Any"), ("Bool", "bool"), ("Bytes", "bytes"), ("Dict", "Dict[{0}, {1}]"), ("Float", "float"),
+                ("Int", "int"), ("List", "List[{0}]"), ("None", "None"), ("Optional", "Optional[{0}]"),
+                ("Result", "Result[{0}, {1}]"), ("Set", "Set[{0}]"), ("String", "str"),
+            ]),
+            javascript: table(&[
+                ("Any", "*"), ("Bool", "boolean"), ("Bytes", "Uint8Array"), ("Dict", "Object<{0}, {1}>"), ("Float", "number"),
+                ("Int", "number"), ("List", "{0}[]"), ("None", "null"), ("Optional", "{0} | undefined"),
+                ("Result", "Result<{0}, {1}>"), ("Set", "Set<{0}>"), ("String", "string"),
+            ]),
+            typescript: table(&[
+                ("Any", "unknown"), ("Bool", "boolean"), ("Bytes", "Uint8Array"), ("Dict", "Record<{0}, {1}>"), ("Float", "number"),
+                ("Int", "number"), ("List", "{0}[]"), ("None", "void"), ("Optional", "{0} | undefined"),
+                ("Result", "Result<{0}, {1}>"), ("Set", "Set<{0}>"), ("String", "string"),
+            ]),
+            rust: table(&[
+                ("Any", "Box<dyn std::any::Any>"), ("Bool", "bool"), ("Bytes", "Vec<u8>"), ("Dict", "HashMap<{0}, {1}>"), ("Float", "f64"),
+                ("Int", "i64"), ("List", "Vec<{0}>"), ("None", "()"), ("Optional", "Option<{0}>"),
+                ("Result", "Result<{0}, {1}>"), ("Set", "HashSet<{0}>"), ("String", "String"),
+            ]),
+            go: table(&[
+                ("Any", "any"), ("Bool", "bool"), ("Bytes", "[]byte"), ("Dict", "map[{0}]{1}"), ("Float", "float64"),
+                ("Int", "int"), ("List", "[]{0}"), ("None", ""), ("Optional", "*{0}"),
+                ("Result", "({0}, error)"), ("Set", "map[{0}]struct{}"), ("String", "string"),
+            ]),
+        }
+    }
+
+    // the standard table with the schema's entries laid over it
+    pub fn with_overrides(&self, overrides: &TypeMappings) -> TypeMappings {
+        let merge = |base: &BTreeMap<String, String>, extra: &BTreeMap<String, String>| {
+            let mut merged = base.clone();
+            merged.extend(extra.iter().map(|(name, template)| (name.clone(), template.clone())));
+            merged
+        };
+        TypeMappings {
+            python: merge(&self.python, &overrides.python),
+            javascript: merge(&self.javascript, &overrides.javascript),
+            typescript: merge(&self.typescript, &overrides.typescript),
+            rust: merge(&self.rust, &overrides.rust),
+            go: merge(&self.go, &overrides.go),
+        }
+    }
+
+    // every constructor named in any language's table
+    pub fn type_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = [&self.python, &self.javascript, &self.typescript, &self.rust, &self.go].iter()
+            .flat_map(|table| table.keys().cloned())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn for_language(&self, language: &str) -> Option<&BTreeMap<String, String>> {
+        match language {
+            "python" => Some(&self.python),
+            "javascript" => Some(&self.javascript),
+            "typescript" => Some(&self.typescript),
+            "rust" => Some(&self.rust),
+            "go" => Some(&self.go),
+            _ => None,
+        }
+    }
+
+    // `None` when the language has no entry for the constructor, or the entry needs more type arguments
+    // than were given such as a bare `List`, the caller then writes it as is
+    pub fn render(&self, language: &str, type_name: &str, children: &[String]) -> Option<String> {
+        let template = self.for_language(language)?.get(type_name)?;
+        if template.contains(&format!("{{{}}}", children.len())) {
+            return None;
+        }
+        let rendered = children.iter().enumerate().fold(template.replace("{..}", &children.join(", ")), |rendered, (index, child)| {
+            rendered.replace(&format!("{{{}}}", index), child)
+        });
+        Some(rendered)
+    }
+
+    // go has no tuple types, a template such as `({0}, error)` is the list of values a function returns
+    fn is_return_only(&self, language: &str, ptype: &ParameterType) -> bool {
+        let type_name = match ptype {
+            ParameterType::ApplicationParentType(value) if value.module_path.is_empty() => &value.type_name,
+            _ => return false,
+        };
+        language == "go" && self.for_language(language).and_then(|table| table.get(type_name)).is_some_and(|template| template.starts_with('('))
+    }
+
+    // why a signature can't be written in the language, if it can't
+    pub fn check_signature(&self, language: &str, signature: &FunctionSignature) -> Option<String> {
+        let mut misplaced: Option<String> = None;
+        let mut visit = |ptype: &ParameterType| {
+            if let ParameterType::ApplicationParentType(value) = ptype {
+                if misplaced.is_none() && self.is_return_only(language, ptype) {
+                    misplaced = Some(value.type_name.clone());
+                }
+            }
+        };
+        signature.input.iter().for_each(|param| param.ptype.walk(&mut visit));
+        match &signature.output {
+            // the return type itself may be one, just not anything inside it
+            ParameterType::ApplicationParentType(value) if self.is_return_only(language, &signature.output) => {
+                value.children.iter().for_each(|child| child.walk(&mut visit));
+            },
+            output => output.walk(&mut visit),
+        }
+        misplaced.map(|type_name| format!("`{}` is only supported as the return type", type_name))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature_parser::parse_signature;
+
+    #[test]
+    fn render_test() {
+        let mappings = TypeMappings::standard();
+        let children = vec![String::from("User"), String::from("ErrorMsg")];
+        assert_eq!(mappings.render("go", "Dict", &children), Some(String::from("map[User]ErrorMsg")));
+        assert_eq!(mappings.render("typescript", "List", &children[..1]), Some(String::from("User[]")));
+        assert_eq!(mappings.render("rust", "Account", &[]), None)
+    }
+
+    #[test]
+    fn with_overrides_test() {
+        let mut overrides = TypeMappings::default();
+        overrides.rust.insert(String::from("Result"), String::from("anyhow::Result<{0}>"));
+        let mappings = TypeMappings::standard().with_overrides(&overrides);
+        let children = vec![String::from("User"), String::from("ErrorMsg")];
+        assert_eq!(mappings.render("rust", "Result", &children), Some(String::from("anyhow::Result<User>")));
+        assert_eq!(mappings.render("rust", "List", &children[..1]), Some(String::from("Vec<User>")))
+    }
+
+    #[test]
+    fn missing_type_arguments_test() {
+        let mappings = TypeMappings::standard();
+        assert_eq!(mappings.render("python", "Dict", &[]), None);
+        assert_eq!(mappings.render("python", "String", &[]), Some(String::from("str")))
+    }
+
+    #[test]
+    fn check_signature_test() {
+        let mappings = TypeMappings::standard();
+        let returned = parse_signature("validate_input(input: DynamoStreamEvent) -> Result[List[AccountId], ErrorMsg]").unwrap();
+        assert_eq!(mappings.check_signature("go", &returned), None);
+        let nested = parse_signature("validate_all(events: List[DynamoStreamEvent]) -> List[Result[AccountId, ErrorMsg]]").unwrap();
+        assert_eq!(mappings.check_signature("go", &nested), Some(String::from("`Result` is only supported as the return type")));
+        let parameter = parse_signature("retry(previous: Result[AccountId, ErrorMsg]) -> Result[AccountId, ErrorMsg]").unwrap();
+        assert_eq!(mappings.check_signature("go", &parameter), Some(String::from("`Result` is only supported as the return type")));
+        // every other language has a Result type which can go anywhere
+        assert_eq!(mappings.check_signature("python", &parameter), None);
+
+        let mut overrides = TypeMappings::default();
+        overrides.go.insert(String::from("Result"), String::from("result.Of[{0}]"));
+        assert_eq!(TypeMappings::standard().with_overrides(&overrides).check_signature("go", &parameter), None)
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use crate::domains::diagnostic::UnknownTypeDiagnostic;
+use crate::domains::file::ProcessedFile;
+use crate::domains::function_signature::{ FunctionSignature, ParameterType };
+use crate::domains::type_definition::TypeDefinition;
+
+
+// constructors every target language has an equivalent for
+pub const BUILT_IN_TYPES: &[&str] = &[
+    "Any", "Bool", "Bytes", "Dict", "Float", "Int", "List", "None", "Optional", "Result", "Set", "String", "Tuple", "Union",
+];
+
+
+// number of single character edits between two names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+
+// the candidate closest to `name`, if it's close enough to have been a typo
+pub fn closest_name<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let allowed = (name.chars().count() / 3).max(1);
+    candidates
+        .map(|candidate| (edit_distance(&name.to_lowercase(), &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= allowed)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+
+// Every type name a signature may use: built-ins, the declared domain types and the system types.
+pub struct TypeRegistry {
+    names: BTreeSet<String>,
+}
+
+impl TypeRegistry {
+    pub fn new(declared: &[TypeDefinition], system_types: &[String]) -> TypeRegistry {
+        let mut names: BTreeSet<String> = BUILT_IN_TYPES.iter().map(|name| name.to_string()).collect();
+        for definition in declared {
+            names.insert(definition.name().to_string());
+            // a sum type's variants can be used on their own too
+            if let TypeDefinition::Sum(sum) = definition {
+                names.extend(sum.variants.iter().map(|variant| variant.name.clone()));
+            }
+        }
+        names.extend(system_types.iter().cloned());
+        TypeRegistry { names }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    pub fn suggest(&self, name: &str) -> Option<&str> {
+        closest_name(name, self.names.iter().map(String::as_str))
+    }
+
+    // names in the signature the registry doesn't know, generic parameters and module qualified types are left alone
+    pub fn unknown_types<'a>(&self, signature: &'a FunctionSignature) -> Vec<&'a str> {
+        let mut unknown: Vec<&str> = vec![];
+        signature.walk_types(&mut |ptype| {
+            let (type_name, module_path) = match ptype {
+                ParameterType::ApplicationType(value) => (value.type_name.as_str(), &value.module_path),
+                ParameterType::ApplicationParentType(value) => (value.type_name.as_str(), &value.module_path),
+                _ => return,
+            };
+            let is_type_parameter = signature.type_parameters.iter().any(|type_parameter| type_parameter == type_name);
+            if module_path.is_empty() && !is_type_parameter && !self.contains(type_name) && !unknown.contains(&type_name) {
+                unknown.push(type_name);
+            }
+        });
+        unknown
+    }
+
+    pub fn check_files(&self, files: &[ProcessedFile]) -> Vec<UnknownTypeDiagnostic> {
+        files.iter().flat_map(|file| file.methods.iter().flat_map(move |method| {
+            self.unknown_types(&method.ast).into_iter().map(move |type_name| UnknownTypeDiagnostic {
+                path: PathBuf::from(&file.path),
+                method_name: method.ast.name.clone(),
+                type_name: type_name.to_string(),
+                suggestion: self.suggest(type_name).map(String::from),
+            })
+        })).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature_parser::parse_signature;
+    use crate::signature_parser::parse_type_definition;
+
+    fn registry() -> TypeRegistry {
+        let declared = vec![
+            parse_type_definition("Account { id: AccountId }").unwrap(),
+            parse_type_definition("Status = Active | Disabled").unwrap(),
+        ];
+        TypeRegistry::new(&declared, &[String::from("AccountId")])
+    }
+
+    #[test]
+    fn unknown_types_test() {
+        let signature = parse_signature(
+            "first<T>(items: List[T], session: boto3.Session, status: Active, id: AcountId) -> Result[Acount, Error]"
+        ).unwrap();
+        assert_eq!(registry().unknown_types(&signature), vec!["AcountId", "Acount", "Error"])
+    }
+
+    #[test]
+    fn suggest_test() {
+        let registry = registry();
+        assert_eq!(registry.suggest("Acount"), Some("Account"));
+        assert_eq!(registry.suggest("accountid"), Some("AccountId"));
+        assert_eq!(registry.suggest("Error"), None)
+    }
+}
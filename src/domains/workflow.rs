@@ -0,0 +1,230 @@
+use std::path::PathBuf;
+
+use crate::domains::diagnostic::{ NameDiagnostic, WorkflowDiagnostic };
+use crate::domains::file::ProcessedFile;
+use crate::domains::function_signature::{ FunctionParameter, FunctionSignature, ParameterType };
+use crate::domains::type_definition::TypeDefinition;
+use crate::domains::type_registry::closest_name;
+
+
+// Every method is imported into the main file's namespace, so its name has to be unique across files,
+// and every workflow step has to be one of those methods. A step naming a duplicated method is covered
+// by the duplicate being reported.
+pub fn check_names(workflow: &[String], files: &[ProcessedFile]) -> Vec<NameDiagnostic> {
+    let mut declared: Vec<(&str, Vec<PathBuf>)> = vec![];
+    for file in files {
+        for method in &file.methods {
+            match declared.iter_mut().find(|(name, _)| *name == method.ast.name) {
+                Some((_, paths)) => paths.push(file.path.clone()),
+                None => declared.push((&method.ast.name, vec![file.path.clone()])),
+            }
+        }
+    }
+
+    let duplicates = declared.iter().filter(|(_, paths)| paths.len() > 1).map(|(name, paths)| {
+        NameDiagnostic::DuplicateMethod { method_name: name.to_string(), paths: paths.clone() }
+    });
+    let unknown_steps = workflow.iter().filter(|step| !declared.iter().any(|(name, _)| name == step)).map(|step| {
+        NameDiagnostic::UnknownStep {
+            step: step.clone(),
+            suggestion: closest_name(step, declared.iter().map(|(name, _)| *name)).map(String::from),
+        }
+    });
+    duplicates.chain(unknown_steps).collect()
+}
+
+
+// Each step of the workflow is handed what the previous step returned, with a `Result` unwrapped to its success type.
+pub struct WorkflowChecker<'a> {
+    types: &'a [TypeDefinition],
+}
+
+impl<'a> WorkflowChecker<'a> {
+    pub fn new(types: &'a [TypeDefinition]) -> WorkflowChecker<'a> {
+        WorkflowChecker { types }
+    }
+
+    // follow aliases such as `TableUsers = List[User]` down to the type they stand for
+    fn resolve(&self, ptype: &'a ParameterType) -> &'a ParameterType {
+        self.resolve_within(ptype, self.types.len())
+    }
+
+    fn resolve_within(&self, ptype: &'a ParameterType, remaining: usize) -> &'a ParameterType {
+        let type_name = match ptype {
+            ParameterType::ApplicationType(value) if value.module_path.is_empty() => &value.type_name,
+            _ => return ptype,
+        };
+        let alias = self.types.iter().find_map(|definition| match definition {
+            TypeDefinition::Alias(alias) if &alias.name == type_name => Some(&alias.target),
+            _ => None,
+        });
+        match alias {
+            Some(target) if remaining > 0 => self.resolve_within(target, remaining - 1),
+            _ => ptype,
+        }
+    }
+
+    // whether a value of type `from` can be passed where `to` is expected
+    fn fits(&self, from: &'a ParameterType, to: &'a ParameterType, target: &FunctionSignature) -> bool {
+        let (from, to) = (self.resolve(from), self.resolve(to));
+        if from == to {
+            return true;
+        }
+        match (from, to) {
+            (_, ParameterType::ApplicationType(value)) if value.module_path.is_empty() => {
+                value.type_name == "Any" || target.type_parameters.contains(&value.type_name)
+            },
+            (ParameterType::UnionType(union), _) => union.members.iter().all(|member| self.fits(member, to, target)),
+            (ParameterType::OptionalType(from_optional), ParameterType::OptionalType(to_optional)) => {
+                self.fits(&from_optional.inner, &to_optional.inner, target)
+            },
+            (_, ParameterType::OptionalType(optional)) => self.fits(from, &optional.inner, target),
+            (_, ParameterType::UnionType(union)) => union.members.iter().any(|member| self.fits(from, member, target)),
+            (ParameterType::ApplicationParentType(from_parent), ParameterType::ApplicationParentType(to_parent)) => {
+                from_parent.type_name == to_parent.type_name
+                    && from_parent.module_path == to_parent.module_path
+                    && from_parent.children.len() == to_parent.children.len()
+                    && from_parent.children.iter().zip(&to_parent.children).all(|(from, to)| self.fits(from, to, target))
+            },
+            _ => false,
+        }
+    }
+
+    // the parameters the workflow has to fill in, dependencies are partially applied and defaults can be left out
+    fn piped_parameters(step: &FunctionSignature) -> Vec<&FunctionParameter> {
+        step.input.iter().filter(|param| {
+            param.default.is_none() && !matches!(param.ptype, ParameterType::Dependency(_))
+        }).collect()
+    }
+
+    // `Result[User, ErrorMsg]` passes a User on to the next step
+    fn success_type(output: &ParameterType) -> &ParameterType {
+        match output {
+            ParameterType::ApplicationParentType(value) if value.type_name == "Result" && value.module_path.is_empty() => {
+                value.children.first().unwrap_or(output)
+            },
+            _ => output,
+        }
+    }
+
+    pub fn check(&self, workflow: &[String], files: &'a [ProcessedFile]) -> Vec<WorkflowDiagnostic> {
+        let find_step = |name: &String| files.iter().flat_map(|file| file.methods.iter()).find(|method| &method.ast.name == name);
+
+        workflow.windows(2).flat_map(|pair| {
+            // unknown step names are reported on their own, there's nothing to compare here
+            let (previous, next) = match (find_step(&pair[0]), find_step(&pair[1])) {
+                (Some(previous), Some(next)) => (&previous.ast, &next.ast),
+                _ => return None,
+            };
+            let diagnostic = |message: String| Some(WorkflowDiagnostic {
+                from_step: previous.name.clone(),
+                to_step: next.name.clone(),
+                message,
+            });
+
+            let success = Self::success_type(&previous.output);
+            match Self::piped_parameters(next).as_slice() {
+                [] => diagnostic(format!("`{}` takes no input for the `{}` returned by `{}`", next.name, success, previous.name)),
+                [param] => {
+                    if self.fits(success, &param.ptype, next) || self.fits(&previous.output, &param.ptype, next) {
+                        None
+                    } else {
+                        diagnostic(format!("`{}` returns `{}` but `{}` expects `{}: {}`", previous.name, success, next.name, param.name, param.ptype))
+                    }
+                },
+                params => diagnostic(format!("`{}` needs {} inputs but only one can be piped into it", next.name, params.len())),
+            }
+        }).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::diagnostic::WorkflowDiagnostics;
+    use crate::domains::method::ParsedMethod;
+    use crate::signature_parser::{ parse_signature, parse_type_definition };
+
+    fn processed_file(path: &str, signatures: &[&str]) -> ProcessedFile {
+        let methods = signatures.iter().map(|signature| ParsedMethod {
+            raw: signature.to_string(),
+            ast: parse_signature(signature).unwrap(),
+        }).collect();
+        ProcessedFile::new(PathBuf::from(path), methods)
+    }
+
+    fn file(signatures: &[&str]) -> Vec<ProcessedFile> {
+        vec![processed_file("src/steps", signatures)]
+    }
+
+    fn workflow(steps: &[&str]) -> Vec<String> {
+        steps.iter().map(|step| step.to_string()).collect()
+    }
+
+    #[test]
+    fn check_names_test() {
+        let files = vec![
+            processed_file("src/domains/user", &["update_user(users: List[User]) -> None"]),
+            processed_file("src/domains/role", &["convert_to_dto(users: List[User]) -> None", "update_user(users: List[User]) -> None"]),
+        ];
+        let result = check_names(&workflow(&["update_users", "convert_to_dto"]), &files);
+        assert_eq!(result, vec![
+            NameDiagnostic::DuplicateMethod {
+                method_name: String::from("update_user"),
+                paths: vec![PathBuf::from("src/domains/user"), PathBuf::from("src/domains/role")],
+            },
+            NameDiagnostic::UnknownStep { step: String::from("update_users"), suggestion: Some(String::from("update_user")) },
+        ])
+    }
+
+    #[test]
+    fn matching_workflow_test() {
+        let types = vec![parse_type_definition("TableUsers = List[User]").unwrap()];
+        let files = file(&[
+            "get_users(http: {requests}, ids: List[UserId]) -> Result[List[User], ErrorMsg]",
+            "convert(users: TableUsers, limit: Int = 10) -> Result[List[User]?, ErrorMsg]",
+            "first<T>(items: List[T]?) -> T",
+            "persist(user: Any) -> None",
+        ]);
+        let checker = WorkflowChecker::new(&types);
+        assert_eq!(checker.check(&workflow(&["get_users", "convert", "first", "persist"]), &files), vec![])
+    }
+
+    #[test]
+    fn mismatched_workflow_test() {
+        let files = file(&[
+            "validate_input(input: Dict) -> Result[DynamoStreamEvent, ErrorMsg]",
+            "get_users(http: {requests}, ids: List[AccountId]) -> Result[List[User], ErrorMsg]",
+            "update(users: List[User], status: Status) -> None",
+        ]);
+        let result = WorkflowChecker::new(&[]).check(&workflow(&["validate_input", "get_users", "update"]), &files);
+        let messages: Vec<String> = result.iter().map(|diagnostic| diagnostic.message.clone()).collect();
+        assert_eq!(messages, vec![
+            "`validate_input` returns `DynamoStreamEvent` but `get_users` expects `ids: List[AccountId]`",
+            "`update` needs 2 inputs but only one can be piped into it",
+        ])
+    }
+
+    #[test]
+    fn previous_example_workflow_test() {
+        // the example schema's chain before its steps were made to line up
+        let types: Vec<TypeDefinition> = ["UserId = String", "AccountId = String", "DynamoStreamEvent = Dict[String, Any]", "TableUsers = List[User]"]
+            .iter().map(|definition| parse_type_definition(definition).unwrap()).collect();
+        let files = file(&[
+            "validate_input(input: Dict) -> Result[DynamoStreamEvent, ErrorMsg]",
+            "get_users_for_account(http_client: {requests}, account_ids: List[AccountId]) -> Result[List[User], ErrorMsg]",
+            "update_user(dynamo: {dynamodb}, user_id: UserId) -> Result[List[User], ErrorMsg]",
+            "convert_to_dto(users: List[User]) -> Result[List[TableUsers], ErrorMsg]",
+            "persist_users(users: List[TableUsers]) -> None",
+        ]);
+        let steps = workflow(&["validate_input", "get_users_for_account", "update_user", "convert_to_dto", "persist_users"]);
+        let diagnostics = WorkflowDiagnostics { diagnostics: WorkflowChecker::new(&types).check(&steps, &files) };
+        assert_eq!(diagnostics.to_string(), [
+            "workflow step validate_input -> get_users_for_account: `validate_input` returns `DynamoStreamEvent` but `get_users_for_account` expects `account_ids: List[AccountId]`",
+            "workflow step get_users_for_account -> update_user: `get_users_for_account` returns `List[User]` but `update_user` expects `user_id: UserId`",
+            "",
+            "workflow has 2 type mismatches",
+        ].join("\n"))
+    }
+}
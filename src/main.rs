@@ -5,17 +5,19 @@ use exitfailure::ExitFailure;
 
 mod signature_parser;
 mod domains;
-mod language_interpreter;
+mod backends;
 
 use crate::domains::schema::Schema;
-use domains::cli::Cli;
+use crate::domains::formatter::format_schema_source;
+use domains::cli::{ Cli, Command };
 
 
 fn main() -> Result<(), ExitFailure> {
-    let schema_path = std::env::args().nth(1).expect("no path given");
-    let args = Cli {
-        schema_path: std::path::PathBuf::from(schema_path),
-    };
+    let args = Cli::from_args(&std::env::args().skip(1).collect::<Vec<String>>());
+    if let Command::Format { check } = args.command {
+        return format_schema(&args.schema_path, check);
+    }
+
     let schema_file_handler = std::fs::File::open(&args.schema_path)?;
     let schema_file: Schema = serde_yaml::from_reader(schema_file_handler)?;
 
@@ -25,3 +27,18 @@ fn main() -> Result<(), ExitFailure> {
         Err(err) => Err(err),
     }
 }
+
+
+fn format_schema(schema_path: &std::path::Path, check: bool) -> Result<(), ExitFailure> {
+    let source = std::fs::read_to_string(schema_path)?;
+    let formatted = format_schema_source(&source)?;
+    if formatted == source {
+        return Ok(());
+    }
+    if check {
+        return Err(failure::err_msg(format!("{} has signatures which are not canonically formatted", schema_path.display())).into());
+    }
+    std::fs::write(schema_path, formatted)?;
+    println!("formatted {}", schema_path.display());
+    Ok(())
+}
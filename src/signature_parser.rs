@@ -1,76 +1,173 @@
 extern crate nom;
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_while, take_until},
-    character::complete::{alphanumeric1, char, multispace0, space0},
-    multi::separated_list0,
-    sequence::{preceded, separated_pair, terminated},
+    bytes::complete::{is_not, tag, take_while1},
+    character::complete::{alphanumeric1, char, digit1, multispace1, space0},
+    combinator::{cut, eof, map, opt, peek, recognize, verify},
+    error::{context, VerboseError, VerboseErrorKind},
+    multi::{many0, separated_list0, separated_list1},
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     IResult
 };
 
+use crate::domains::diagnostic::SignatureError;
 use crate::domains::function_signature::{ 
-    ApplicationType, ApplicationParentType, 
+    ApplicationType, ApplicationParentType, OptionalType, UnionType, TupleType, FunctionType, LiteralType,
     FunctionParameter, FunctionSignature, Dependency, ParameterType
 };
+use crate::domains::type_definition::{ TypeDefinition, TypeField, RecordType, SumType, AliasType };
 
 
-fn valid_type_identifier_char(c: char) -> bool {
-    c.is_alphanumeric() || c == '[' || c == ']' || c == '{' || c == '}'
+type ParseResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+
+// `# ...` runs to the end of the line
+fn comment(i: &str) -> ParseResult<'_, &str> {
+    recognize(pair(char('#'), opt(is_not("\n"))))(i)
+}
+
+
+// spaces, newlines and comments are all allowed wherever whitespace is
+fn ws(i: &str) -> ParseResult<'_, &str> {
+    recognize(many0(alt((multispace1, comment))))(i)
 }
 
 
-fn valid_type_identifier(i: &str) -> IResult<&str, &str> {
-    take_while(valid_type_identifier_char)(i)
+fn comma(i: &str) -> ParseResult<'_, char> {
+    preceded(ws, char(','))(i)
 }
 
+
 fn valid_identifier_char(c: char) -> bool {
     c.is_alphanumeric() || c == '_'
 }
 
 
-fn valid_identifier(i: &str) -> IResult<&str, &str> {
-    take_while(valid_identifier_char)(i)
+fn valid_identifier(i: &str) -> ParseResult<'_, &str> {
+    take_while1(valid_identifier_char)(i)
+}
+
+
+fn parse_function_name(i: &str) -> ParseResult<'_, &str> {
+    context("expected a function name", terminated(preceded(ws, valid_identifier), ws))(i)
 }
 
 
-fn parse_function_name(i: &str) -> IResult<&str, &str> {
-    take_until("(")(i)
+// `models.User` keeps each segment: the module path ["models"] and the type name "User"
+fn parse_type_path(i: &str) -> ParseResult<'_, (Vec<String>, String)> {
+    let (rest, mut segments) = separated_list1(char('.'), valid_identifier)(i)?;
+    let type_name = segments.pop().unwrap_or_default();
+    Ok((rest, (segments.into_iter().map(String::from).collect(), String::from(type_name))))
 }
 
 
-fn parse_application_type(i: &str) -> IResult<&str, ParameterType> {
-    let (rest, result) = alphanumeric1(i)?;
+fn parse_application_type(i: &str) -> ParseResult<'_, ParameterType> {
+    let (rest, (module_path, type_name)) = parse_type_path(i)?;
     Ok((
-        rest, ParameterType::ApplicationType( ApplicationType { type_name: String::from(result) } )
+        rest, ParameterType::ApplicationType( ApplicationType { type_name, module_path } )
     ))
 }
 
 
-fn parse_parent_type(i: &str) -> IResult<&str, ParameterType> {
-    let (bracket_rest, type_name) = alphanumeric1(i)?;
+fn parse_parent_type(i: &str) -> ParseResult<'_, ParameterType> {
+    let (bracket_rest, (module_path, type_name)) = parse_type_path(i)?;
     
-    let (rest, bracket_contents) = preceded(tag("["), terminated(separated_list0(tag(","), parse_type), tag("]")))(bracket_rest)?;
+    let (rest, bracket_contents) = preceded(
+        tag("["),
+        cut(terminated(
+            terminated(separated_list0(comma, parse_type), opt(comma)),
+            context("expected `,` or `]` to close the type parameters", preceded(ws, tag("]")))
+        ))
+    )(bracket_rest)?;
 
     let param = ParameterType::ApplicationParentType(
-        ApplicationParentType { type_name: String::from(type_name), children: bracket_contents }
+        ApplicationParentType { type_name, module_path, children: bracket_contents }
     );
 
     Ok((rest, param))
 }
 
 
-fn parse_dependency_type(i: &str) -> IResult<&str, ParameterType> {
-    let (rest, result) = preceded(preceded(space0, tag("{")), terminated(alphanumeric1, tag("}")))(i)?;
+// the methods a step calls on its dependency, as `{requests: get, post}` or `{dynamodb.update_item}`
+fn parse_dependency_methods(i: &str) -> ParseResult<'_, Vec<&str>> {
+    let method_name = || context("expected a method name", preceded(ws, valid_identifier));
+    map(
+        opt(alt((
+            preceded(char('.'), cut(map(method_name(), |method| vec![method]))),
+            preceded(preceded(ws, char(':')), cut(terminated(separated_list1(comma, method_name()), opt(comma)))),
+        ))),
+        |methods| methods.unwrap_or_default()
+    )(i)
+}
+
+
+fn parse_dependency_type(i: &str) -> ParseResult<'_, ParameterType> {
+    let (rest, (result, methods)) = preceded(
+        tag("{"),
+        cut(terminated(
+            pair(context("expected a dependency name", preceded(ws, alphanumeric1)), parse_dependency_methods),
+            context("expected `}` to close the dependency", preceded(ws, tag("}")))
+        ))
+    )(i)?;
+
+    let param = ParameterType::Dependency ( Dependency{
+        dependency_name: String::from(result),
+        methods: methods.into_iter().map(String::from).collect(),
+    });
+    Ok((rest, param))
+}
+
+
+// `(AccountId, User)` is a tuple and `(AccountId) -> User` is a function,
+// while a single parenthesised type is only grouping unless it has a trailing comma
+fn parse_parenthesised_type(i: &str) -> ParseResult<'_, ParameterType> {
+    let (rest, (mut members, trailing_comma)) = preceded(
+        tag("("),
+        cut(terminated(
+            pair(separated_list0(comma, parse_type), opt(comma)),
+            context("expected `,` or `)` to close the tuple", preceded(ws, tag(")")))
+        ))
+    )(i)?;
+
+    let (rest, output) = opt(preceded(preceded(ws, tag("->")), cut(parse_type)))(rest)?;
+    if let Some(output) = output {
+        let param = ParameterType::Function(FunctionType { params: members, output: Box::new(output) });
+        return Ok((rest, param));
+    }
+
+    let param = if members.len() == 1 && trailing_comma.is_none() {
+        members.remove(0)
+    } else {
+        ParameterType::TupleType(TupleType { members })
+    };
+    Ok((rest, param))
+}
+
+
+// `Literal['active', 'disabled']` lists the exact values a parameter may take
+fn parse_literal_type(i: &str) -> ParseResult<'_, ParameterType> {
+    let (rest, values) = preceded(
+        tag("Literal["),
+        cut(terminated(
+            terminated(
+                separated_list1(comma, context("expected a literal value", preceded(ws, alt((parse_literal, valid_identifier))))),
+                opt(comma)
+            ),
+            context("expected `,` or `]` to close the literal values", preceded(ws, tag("]")))
+        ))
+    )(i)?;
 
-    let param = ParameterType::Dependency ( Dependency{dependency_name: String::from(result) });
+    let param = ParameterType::LiteralType(LiteralType { values: values.into_iter().map(String::from).collect() });
     Ok((rest, param))
 }
 
 
-fn parse_type(i: &str) -> IResult<&str, ParameterType> {
+fn parse_primary_type(i: &str) -> ParseResult<'_, ParameterType> {
     preceded(
-        space0,
+        ws,
         alt((
+            parse_literal_type,
+            parse_parenthesised_type,
             parse_dependency_type,
             parse_parent_type,
             parse_application_type,
@@ -79,50 +176,299 @@ fn parse_type(i: &str) -> IResult<&str, ParameterType> {
 }
 
 
-fn parse_argument(i: &str) -> IResult<&str, FunctionParameter> {
-    let (rest, (left, right)) = separated_pair(
-        preceded(space0, valid_identifier), 
-        preceded(space0, char(':')), 
-        preceded(space0, valid_type_identifier)
+// `User?` is shorthand for an optional User
+fn parse_optional_type(i: &str) -> ParseResult<'_, ParameterType> {
+    map(pair(parse_primary_type, opt(char('?'))), |(ptype, optional)| match optional {
+        Some(_) => ParameterType::OptionalType(OptionalType { inner: Box::new(ptype) }),
+        None => ptype,
+    })(i)
+}
+
+
+fn parse_type(i: &str) -> ParseResult<'_, ParameterType> {
+    context(
+        "expected a type",
+        map(separated_list1(preceded(ws, char('|')), parse_optional_type), |mut members| {
+            if members.len() == 1 {
+                members.remove(0)
+            } else {
+                ParameterType::UnionType(UnionType { members })
+            }
+        })
+    )(i)
+}
+
+
+// a number or a quoted string, kept as written
+fn parse_literal(i: &str) -> ParseResult<'_, &str> {
+    alt((
+        recognize(tuple((opt(char('-')), digit1, opt(pair(char('.'), digit1))))),
+        recognize(delimited(char('\''), opt(is_not("'")), char('\''))),
+        recognize(delimited(char('"'), opt(is_not("\"")), char('"'))),
+    ))(i)
+}
+
+
+// defaults are kept as written: literals, bare names such as None, or empty collections
+fn parse_default_value(i: &str) -> ParseResult<'_, &str> {
+    context(
+        "expected a default value",
+        preceded(
+            ws,
+            alt((
+                parse_literal,
+                tag("[]"),
+                tag("{}"),
+                valid_identifier,
+            ))
+        )
+    )(i)
+}
+
+
+fn parse_argument(i: &str) -> ParseResult<'_, FunctionParameter> {
+    let (rest, (left, application_type)) = separated_pair(
+        preceded(ws, valid_identifier), 
+        cut(context("expected `:` after the parameter name", preceded(ws, char(':')))), 
+        cut(parse_type)
     )(i)?;
-    let (_, application_type) = parse_type(right)?;
-    let param = FunctionParameter { name: String::from(left), ptype: application_type };
+    let (rest, default) = opt(preceded(preceded(ws, char('=')), cut(parse_default_value)))(rest)?;
+    let param = FunctionParameter {
+        name: String::from(left),
+        ptype: application_type,
+        default: default.map(String::from),
+    };
     Ok((rest, param))
 }
 
 
-fn parse_function_arguments(i: &str) -> IResult<&str, Vec<FunctionParameter>> {
-    preceded(tag("("), 
+// each argument along with the input it starts at, so a misplaced one can be pointed at
+fn parse_positioned_argument(i: &str) -> ParseResult<'_, (&str, FunctionParameter)> {
+    let (rest, param) = parse_argument(i)?;
+    Ok((rest, (ws(i)?.0, param)))
+}
+
+
+fn parse_function_arguments(i: &str) -> ParseResult<'_, Vec<FunctionParameter>> {
+    let (rest, arguments) = preceded(context("expected `(` after the function name", tag("(")), 
         terminated(
-            separated_list0(
-                preceded(space0, tag(",")), preceded(multispace0, parse_argument)
-            ), 
-            tag(")")
+            terminated(separated_list0(comma, parse_positioned_argument), opt(comma)),
+            context("expected `,` or `)` to close the parameter list", preceded(ws, tag(")")))
         )
-    )(i)
+    )(i)?;
+
+    // a required parameter can't follow a defaulted one, callers couldn't leave the default out
+    let first_default = arguments.iter().position(|(_, param)| param.default.is_some());
+    let misplaced = first_default.and_then(|index| arguments[index..].iter().find(|(_, param)| param.default.is_none()));
+    if let Some((position, _)) = misplaced {
+        let message = "expected a default value, a required parameter can't follow a defaulted one";
+        return Err(nom::Err::Failure(VerboseError { errors: vec![(*position, VerboseErrorKind::Context(message))] }));
+    }
+    Ok((rest, arguments.into_iter().map(|(_, param)| param).collect()))
 }
 
-fn parse_output(i: &str) -> IResult<&str, ParameterType> {
+fn parse_output(i: &str) -> ParseResult<'_, ParameterType> {
     preceded(
-        preceded(space0, tag("->")), 
-            preceded(space0, parse_type)
+        context("expected `->` followed by the return type", preceded(ws, tag("->"))), 
+            cut(parse_type)
     )(i)
 }
 
-    
-#[allow(dead_code)]
-pub fn root(i: &str) -> IResult<&str, FunctionSignature> {
-    let (rest, function_name) = parse_function_name(i)?;
+
+fn parse_async_marker(i: &str) -> ParseResult<'_, bool> {
+    map(opt(preceded(ws, terminated(tag("async"), multispace1))), |marker| marker.is_some())(i)
+}
+
+
+// `first<T>(...)` declares T for use in the parameter and output types
+fn parse_type_parameters(i: &str) -> ParseResult<'_, Vec<&str>> {
+    map(
+        opt(preceded(
+            char('<'),
+            cut(terminated(
+                terminated(
+                    separated_list1(comma, context("expected a type parameter name", preceded(ws, valid_identifier))),
+                    opt(comma)
+                ),
+                context("expected `,` or `>` to close the type parameters", preceded(ws, char('>')))
+            ))
+        )),
+        |type_parameters| type_parameters.unwrap_or_default()
+    )(i)
+}
+
+
+
+// `#: ...` lines above the signature describe the method, any other `#` line is just a comment
+// and may come before, between or after them
+fn parse_doc_comment(i: &str) -> ParseResult<'_, Option<String>> {
+    let doc_line = map(preceded(pair(tag("#:"), space0), opt(is_not("\n"))), |line: Option<&str>| Some(line.unwrap_or("").trim_end()));
+    let skipped = map(alt((multispace1, comment)), |_| None);
+    map(many0(alt((doc_line, skipped))), |lines| {
+        let lines: Vec<&str> = lines.into_iter().flatten().collect();
+        if lines.is_empty() { None } else { Some(lines.join(" ")) }
+    })(i)
+}
+
+
+pub fn root(i: &str) -> ParseResult<'_, FunctionSignature> {
+    let (rest, description) = parse_doc_comment(i)?;
+    let (rest, is_async) = parse_async_marker(rest)?;
+    let (rest, function_name) = parse_function_name(rest)?;
+    let (rest, type_parameters) = terminated(parse_type_parameters, ws)(rest)?;
     let (rest, params) = parse_function_arguments(rest)?;
-    let (_, output) = parse_output(rest)?;
+    let (rest, output) = parse_output(rest)?;
+    let (rest, _) = context("expected the end of the signature", preceded(ws, eof))(rest)?;
 
     let function_signature = FunctionSignature {
         name: String::from(function_name),
         input: params,
         output,
+        is_async,
+        type_parameters: type_parameters.into_iter().map(String::from).collect(),
+        description,
     };
 
-    Ok(("", function_signature))
+    Ok((rest, function_signature))
+}
+
+
+fn parse_type_field(i: &str) -> ParseResult<'_, TypeField> {
+    let (rest, (name, ftype)) = separated_pair(
+        context("expected a field name", preceded(ws, valid_identifier)),
+        cut(context("expected `:` after the field name", preceded(ws, char(':')))),
+        cut(parse_type)
+    )(i)?;
+    Ok((rest, TypeField { name: String::from(name), ftype }))
+}
+
+
+// `{ id: UserId, email: Email }`
+fn parse_record_fields(i: &str) -> ParseResult<'_, Vec<TypeField>> {
+    preceded(
+        preceded(ws, char('{')),
+        cut(terminated(
+            terminated(separated_list0(comma, parse_type_field), opt(comma)),
+            context("expected `,` or `}` to close the fields", preceded(ws, char('}')))
+        ))
+    )(i)
+}
+
+
+fn parse_variant(i: &str) -> ParseResult<'_, RecordType> {
+    let (rest, (name, fields)) = pair(preceded(ws, valid_identifier), opt(parse_record_fields))(i)?;
+    Ok((rest, RecordType { name: String::from(name), fields: fields.unwrap_or_default() }))
+}
+
+
+// `Active | Disabled { reason: String }`, anything which isn't a list of variants is an alias
+fn parse_variants(i: &str) -> ParseResult<'_, Vec<RecordType>> {
+    verify(
+        terminated(separated_list1(preceded(ws, char('|')), parse_variant), peek(preceded(ws, eof))),
+        |variants: &[RecordType]| variants.len() > 1 || variants.iter().any(|variant| !variant.fields.is_empty())
+    )(i)
+}
+
+
+// `User { id: UserId }` declares a record, `Status = Active | Disabled` a sum type and `UserId = String` an alias
+fn type_definition_root(i: &str) -> ParseResult<'_, TypeDefinition> {
+    let (rest, name) = context("expected a type name", preceded(ws, valid_identifier))(i)?;
+    let name = String::from(name);
+    let (rest, definition) = alt((
+        map(parse_record_fields, |fields| TypeDefinition::Record(RecordType { name: name.clone(), fields })),
+        preceded(
+            context("expected `{` or `=` after the type name", preceded(ws, char('='))),
+            cut(alt((
+                map(parse_variants, |variants| TypeDefinition::Sum(SumType { name: name.clone(), variants })),
+                map(parse_type, |target| TypeDefinition::Alias(AliasType { name: name.clone(), target })),
+            )))
+        ),
+    ))(rest)?;
+    let (rest, _) = context("expected the end of the type definition", preceded(ws, eof))(rest)?;
+    Ok((rest, definition))
+}
+
+
+fn is_token_boundary(c: char) -> bool {
+    c.is_whitespace() || "()[]{},:".contains(c)
+}
+
+
+// 1-based line and column of a byte offset, counting columns in characters
+fn line_and_column(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+
+// Turn nom's error stack into something a developer can act on: the position of the
+// innermost failure and the closest context message wrapped around it.
+fn to_signature_error(input: &str, error: VerboseError<&str>) -> SignatureError {
+    let remaining = error.errors.first().map(|(rest, _)| *rest).unwrap_or("");
+    let offset = input.len() - remaining.len();
+
+    let expected = error.errors.iter().find_map(|(_, kind)| match kind {
+        VerboseErrorKind::Context(message) => Some(message.to_string()),
+        _ => None,
+    }).unwrap_or_else(|| match error.errors.first() {
+        Some((_, VerboseErrorKind::Char(c))) => format!("expected `{}`", c),
+        _ => String::from("unexpected input"),
+    });
+
+    let token_length = remaining.chars().take_while(|c| !is_token_boundary(*c)).count();
+    let (line, column) = line_and_column(input, offset);
+
+    SignatureError {
+        line,
+        column,
+        length: token_length.max(1),
+        expected,
+    }
+}
+
+
+pub fn parse_signature(i: &str) -> Result<FunctionSignature, SignatureError> {
+    to_result(i, root(i))
+}
+
+
+pub fn parse_type_definition(i: &str) -> Result<TypeDefinition, SignatureError> {
+    to_result(i, type_definition_root(i))
+}
+
+
+// A variant found to redeclare an existing type once every definition has been parsed,
+// pointed at where it's written after the `=`.
+pub fn clashing_variant_error(definition: &str, variant: &str) -> SignatureError {
+    let start = definition.find('=').unwrap_or(0);
+    let offset = definition[start..].match_indices(variant)
+        .map(|(index, _)| start + index)
+        .find(|index| {
+            let before = definition[..*index].chars().last();
+            let after = definition[index + variant.len()..].chars().next();
+            !before.is_some_and(valid_identifier_char) && !after.is_some_and(valid_identifier_char)
+        })
+        .unwrap_or(start);
+    let message = "expected a variant which isn't a declared type, or only declared types to declare a union";
+    to_signature_error(definition, VerboseError { errors: vec![(&definition[offset..], VerboseErrorKind::Context(message))] })
+}
+
+
+fn to_result<T>(i: &str, parsed: ParseResult<'_, T>) -> Result<T, SignatureError> {
+    match parsed {
+        Ok((_, value)) => Ok(value),
+        Err(nom::Err::Error(error)) | Err(nom::Err::Failure(error)) => Err(to_signature_error(i, error)),
+        Err(nom::Err::Incomplete(_)) => {
+            let (line, column) = line_and_column(i, i.len());
+            Err(SignatureError {
+                line,
+                column,
+                length: 1,
+                expected: String::from("unexpected end of signature"),
+            })
+        },
+    }
 }
 
 
@@ -139,8 +485,9 @@ mod tests {
                 FunctionParameter { 
                     name: String::from("http_client"), 
                     ptype: ParameterType::Dependency(
-                        Dependency { dependency_name: String::from("requests") }
-                    )
+                        Dependency { dependency_name: String::from("requests"), methods: vec![] }
+                    ),
+                    default: None
                 },
                 FunctionParameter {
                     name: String::from("account_ids"), 
@@ -149,11 +496,13 @@ mod tests {
                             type_name: String::from("List"),
                             children: vec![
                                 ParameterType::ApplicationType(
-                                    ApplicationType { type_name: String::from("AccountId") }
+                                    ApplicationType { type_name: String::from("AccountId"), module_path: vec![] }
                                 )
-                            ]
+                            ],
+                            module_path: vec![]
                         }
-                    )
+                    ),
+                    default: None
                 }
             ], 
             output: ParameterType::ApplicationParentType(
@@ -165,17 +514,22 @@ mod tests {
                                 type_name: String::from("List"),
                                 children: vec![
                                     ParameterType::ApplicationType(
-                                        ApplicationType { type_name: String::from("User") }
+                                        ApplicationType { type_name: String::from("User"), module_path: vec![] }
                                     )
-                                ]
+                                ],
+                                module_path: vec![]
                             }
                         ),
                         ParameterType::ApplicationType(
-                            ApplicationType { type_name: String::from("ErrorMsg") }
+                            ApplicationType { type_name: String::from("ErrorMsg"), module_path: vec![] }
                         )
-                    ]
+                    ],
+                    module_path: vec![]
                 }
-            )
+            ),
+            is_async: false,
+            type_parameters: vec![],
+            description: None
         };
         let result = root(data);
         assert_eq!(result, Ok(("", expected)))
@@ -186,7 +540,7 @@ mod tests {
         let data = "AccountId";
         let result = parse_type(data);
         let expected = ParameterType::ApplicationType(
-            ApplicationType { type_name: String::from("AccountId") }
+            ApplicationType { type_name: String::from("AccountId"), module_path: vec![] }
         );
         assert_eq!(result, Ok(("", expected)))
     }
@@ -200,9 +554,10 @@ mod tests {
                 type_name: String::from("AccountId"),
                 children: vec![
                     ParameterType::ApplicationType(
-                        ApplicationType { type_name: String::from("User") }
+                        ApplicationType { type_name: String::from("User"), module_path: vec![] }
                     )
-                ]
+                ],
+                module_path: vec![]
             }
         );
         assert_eq!(result, Ok(("", expected)))
@@ -221,12 +576,35 @@ mod tests {
                             type_name: String::from("User"),
                             children: vec![
                                 ParameterType::ApplicationType(
-                                    ApplicationType { type_name: String::from("Email") }
+                                    ApplicationType { type_name: String::from("Email"), module_path: vec![] }
                                 )
-                            ]
+                            ],
+                            module_path: vec![]
                         }
                     )
-                ]
+                ],
+                module_path: vec![]
+            }
+        );
+        assert_eq!(result, Ok(("", expected)))
+    }
+
+    #[test]
+    fn parse_qualified_type_test() {
+        let data = "List[models.user_types.User]";
+        let result = parse_type(data);
+        let expected = ParameterType::ApplicationParentType(
+            ApplicationParentType {
+                type_name: String::from("List"),
+                children: vec![
+                    ParameterType::ApplicationType(
+                        ApplicationType {
+                            type_name: String::from("User"),
+                            module_path: vec![String::from("models"), String::from("user_types")]
+                        }
+                    )
+                ],
+                module_path: vec![]
             }
         );
         assert_eq!(result, Ok(("", expected)))
@@ -239,21 +617,417 @@ mod tests {
         let expected = ParameterType::Dependency(
             Dependency { 
                 dependency_name: String::from("requests"),
+                methods: vec![]
             }
         );
         assert_eq!(result, Ok(("", expected)))
     }
     
+    #[test]
+    fn parse_optional_type_test() {
+        let data = "List[User?]?";
+        let result = parse_type(data);
+        let expected = ParameterType::OptionalType(
+            OptionalType {
+                inner: Box::new(ParameterType::ApplicationParentType(
+                    ApplicationParentType {
+                        type_name: String::from("List"),
+                        children: vec![
+                            ParameterType::OptionalType(
+                                OptionalType {
+                                    inner: Box::new(ParameterType::ApplicationType(
+                                        ApplicationType { type_name: String::from("User"), module_path: vec![] }
+                                    ))
+                                }
+                            )
+                        ],
+                        module_path: vec![]
+                    }
+                ))
+            }
+        );
+        assert_eq!(result, Ok(("", expected)))
+    }
+
+    #[test]
+    fn parse_union_type_test() {
+        let data = "User | Admin?";
+        let result = parse_type(data);
+        let expected = ParameterType::UnionType(
+            UnionType {
+                members: vec![
+                    ParameterType::ApplicationType(
+                        ApplicationType { type_name: String::from("User"), module_path: vec![] }
+                    ),
+                    ParameterType::OptionalType(
+                        OptionalType {
+                            inner: Box::new(ParameterType::ApplicationType(
+                                ApplicationType { type_name: String::from("Admin"), module_path: vec![] }
+                            ))
+                        }
+                    )
+                ]
+            }
+        );
+        assert_eq!(result, Ok(("", expected)))
+    }
+
+    #[test]
+    fn parse_tuple_type_test() {
+        let data = "(AccountId, List[User])";
+        let result = parse_type(data);
+        let expected = ParameterType::TupleType(
+            TupleType {
+                members: vec![
+                    ParameterType::ApplicationType(
+                        ApplicationType { type_name: String::from("AccountId"), module_path: vec![] }
+                    ),
+                    ParameterType::ApplicationParentType(
+                        ApplicationParentType {
+                            type_name: String::from("List"),
+                            children: vec![
+                                ParameterType::ApplicationType(
+                                    ApplicationType { type_name: String::from("User"), module_path: vec![] }
+                                )
+                            ],
+                            module_path: vec![]
+                        }
+                    )
+                ]
+            }
+        );
+        assert_eq!(result, Ok(("", expected)))
+    }
+
+    #[test]
+    fn parse_grouped_union_type_test() {
+        let data = "(User | Admin)?";
+        let result = parse_type(data);
+        let expected = ParameterType::OptionalType(
+            OptionalType {
+                inner: Box::new(ParameterType::UnionType(
+                    UnionType {
+                        members: vec![
+                            ParameterType::ApplicationType(
+                                ApplicationType { type_name: String::from("User"), module_path: vec![] }
+                            ),
+                            ParameterType::ApplicationType(
+                                ApplicationType { type_name: String::from("Admin"), module_path: vec![] }
+                            )
+                        ]
+                    }
+                ))
+            }
+        );
+        assert_eq!(result, Ok(("", expected)))
+    }
+
+    #[test]
+    fn parse_function_type_test() {
+        let data = r#"retry(op: (AccountId) -> Result[User, ErrorMsg]) -> User"#;
+        let expected = FunctionSignature {
+            name: String::from("retry"),
+            input: vec![
+                FunctionParameter {
+                    name: String::from("op"),
+                    ptype: ParameterType::Function(
+                        FunctionType {
+                            params: vec![
+                                ParameterType::ApplicationType(
+                                    ApplicationType { type_name: String::from("AccountId"), module_path: vec![] }
+                                )
+                            ],
+                            output: Box::new(ParameterType::ApplicationParentType(
+                                ApplicationParentType {
+                                    type_name: String::from("Result"),
+                                    children: vec![
+                                        ParameterType::ApplicationType(
+                                            ApplicationType { type_name: String::from("User"), module_path: vec![] }
+                                        ),
+                                        ParameterType::ApplicationType(
+                                            ApplicationType { type_name: String::from("ErrorMsg"), module_path: vec![] }
+                                        )
+                                    ],
+                                    module_path: vec![]
+                                }
+                            ))
+                        }
+                    ),
+                    default: None
+                }
+            ],
+            output: ParameterType::ApplicationType(
+                ApplicationType { type_name: String::from("User"), module_path: vec![] }
+            ),
+            is_async: false,
+            type_parameters: vec![],
+            description: None
+        };
+        let result = root(data);
+        assert_eq!(result, Ok(("", expected)))
+    }
+
+    #[test]
+    fn parse_dependency_methods_test() {
+        let result = parse_type("{requests: get, post}");
+        let expected = ParameterType::Dependency(
+            Dependency {
+                dependency_name: String::from("requests"),
+                methods: vec![String::from("get"), String::from("post")]
+            }
+        );
+        assert_eq!(result, Ok(("", expected)));
+
+        let result = parse_type("{dynamodb.update_item}");
+        let expected = ParameterType::Dependency(
+            Dependency {
+                dependency_name: String::from("dynamodb"),
+                methods: vec![String::from("update_item")]
+            }
+        );
+        assert_eq!(result, Ok(("", expected)))
+    }
+
+    #[test]
+    fn parse_literal_type_test() {
+        let data = "Literal['active','disabled']";
+        let result = parse_type(data);
+        let expected = ParameterType::LiteralType(
+            LiteralType { values: vec![String::from("'active'"), String::from("'disabled'")] }
+        );
+        assert_eq!(result, Ok(("", expected)))
+    }
+
     #[test]
     fn parse_argument_test() {
         let data = "id: AccountId";
         let result = parse_argument(data);
-        let application_type = ApplicationType { type_name: String::from("AccountId") };
-        let expected = FunctionParameter { name: String::from("id"), ptype: ParameterType::ApplicationType(application_type) };
+        let application_type = ApplicationType { type_name: String::from("AccountId"), module_path: vec![] };
+        let expected = FunctionParameter { name: String::from("id"), ptype: ParameterType::ApplicationType(application_type), default: None };
+        assert_eq!(result, Ok(("", expected)))
+    }
+
+    #[test]
+    fn parse_default_argument_test() {
+        let data = "limit: Int = 100";
+        let result = parse_argument(data);
+        let application_type = ApplicationType { type_name: String::from("Int"), module_path: vec![] };
+        let expected = FunctionParameter {
+            name: String::from("limit"),
+            ptype: ParameterType::ApplicationType(application_type),
+            default: Some(String::from("100"))
+        };
         assert_eq!(result, Ok(("", expected)))
     }
 
-    // #[test]
-    // fn invalid_syntax_function_signature_test() {
-    // }
+    #[test]
+    fn parse_quoted_default_argument_test() {
+        let data = "get_users(region: Str = 'eu-west-1', limit: Int = -1) -> None";
+        let (_, result) = root(data).unwrap();
+        let defaults: Vec<Option<String>> = result.input.into_iter().map(|param| param.default).collect();
+        assert_eq!(defaults, vec![Some(String::from("'eu-west-1'")), Some(String::from("-1"))])
+    }
+
+    #[test]
+    fn parse_async_function_test() {
+        let (_, result) = root("async get_account(http: {aiohttp}, id: AccountId) -> Account").unwrap();
+        assert!(result.is_async);
+        assert_eq!(result.name, "get_account");
+
+        let (_, result) = root("async_cleanup() -> None").unwrap();
+        assert!(!result.is_async);
+        assert_eq!(result.name, "async_cleanup");
+    }
+
+    #[test]
+    fn parse_generic_function_test() {
+        let (_, result) = root("first<T, U>(items: List[T], fallback: U) -> T | U").unwrap();
+        assert_eq!(result.name, "first");
+        assert_eq!(result.type_parameters, vec![String::from("T"), String::from("U")]);
+    }
+
+    #[test]
+    fn multi_line_function_signature_test() {
+        let single_line = "get_users_for_account(http_client: {requests: get, post}, account_ids: List[AccountId]) -> Result[List[User], ErrorMsg]";
+        let multi_line = r#"get_users_for_account(
+    http_client: {requests: get, post,},  # the shared session
+    account_ids: List[AccountId,],
+) -> Result[
+    List[User],
+    ErrorMsg,
+]"#;
+        assert_eq!(root(multi_line), root(single_line))
+    }
+
+    #[test]
+    fn doc_comment_function_signature_test() {
+        let data = "#: Fetches the users of an account\n#: from the accounts api\n# not part of the description\nget_users(id: AccountId) -> Users";
+        let (_, result) = root(data).unwrap();
+        assert_eq!(result.description, Some(String::from("Fetches the users of an account from the accounts api")));
+        assert_eq!(result.name, "get_users");
+    }
+
+    #[test]
+    fn mixed_comment_header_test() {
+        let data = "# generated from the accounts api
+
+#: Fetches the users of an account
+# TODO: paginate
+#: from the accounts api
+async get_users(id: AccountId) -> Users";
+        let (_, result) = root(data).unwrap();
+        assert_eq!(result.description, Some(String::from("Fetches the users of an account from the accounts api")));
+        assert!(result.is_async);
+    }
+
+    #[test]
+    fn trailing_comma_tuple_test() {
+        let single = ParameterType::TupleType(
+            TupleType {
+                members: vec![
+                    ParameterType::ApplicationType(
+                        ApplicationType { type_name: String::from("User"), module_path: vec![] }
+                    )
+                ]
+            }
+        );
+        assert_eq!(parse_type("(User,)"), Ok(("", single)))
+    }
+
+    #[test]
+    fn multi_line_error_position_test() {
+        let data = "get_account(\n    id AccountId\n) -> Account";
+        let expected = SignatureError {
+            line: 2,
+            column: 8,
+            length: 9,
+            expected: String::from("expected `:` after the parameter name"),
+        };
+        assert_eq!(parse_signature(data), Err(expected))
+    }
+
+    #[test]
+    fn invalid_syntax_function_signature_test() {
+        let data = "validate_input(input Dict) -> Result[DynamoStreamEvent, ErrorMsg]";
+        let expected = SignatureError {
+            line: 1,
+            column: 22,
+            length: 4,
+            expected: String::from("expected `:` after the parameter name"),
+        };
+        assert_eq!(parse_signature(data), Err(expected))
+    }
+
+    #[test]
+    fn trailing_input_function_signature_test() {
+        let data = "get_account(id: AccountId) -> Account junk here";
+        let expected = SignatureError {
+            line: 1,
+            column: 39,
+            length: 4,
+            expected: String::from("expected the end of the signature"),
+        };
+        assert_eq!(parse_signature(data), Err(expected))
+    }
+
+    #[test]
+    fn trailing_type_input_argument_test() {
+        let data = "get_account(id: AccountId extra) -> Account";
+        let expected = SignatureError {
+            line: 1,
+            column: 27,
+            length: 5,
+            expected: String::from("expected `,` or `)` to close the parameter list"),
+        };
+        assert_eq!(parse_signature(data), Err(expected))
+    }
+
+    #[test]
+    fn invalid_function_name_test() {
+        let data = "get account(id: AccountId) -> Account";
+        let expected = SignatureError {
+            line: 1,
+            column: 5,
+            length: 7,
+            expected: String::from("expected `(` after the function name"),
+        };
+        assert_eq!(parse_signature(data), Err(expected))
+    }
+
+    #[test]
+    fn required_after_default_argument_test() {
+        let data = "get_account(limit: Int = 100, id: AccountId) -> Account";
+        let expected = SignatureError {
+            line: 1,
+            column: 31,
+            length: 2,
+            expected: String::from("expected a default value, a required parameter can't follow a defaulted one"),
+        };
+        assert_eq!(parse_signature(data), Err(expected))
+    }
+
+    #[test]
+    fn missing_output_function_signature_test() {
+        let data = "get_account(id: AccountId)";
+        let expected = SignatureError {
+            line: 1,
+            column: 27,
+            length: 1,
+            expected: String::from("expected `->` followed by the return type"),
+        };
+        assert_eq!(parse_signature(data), Err(expected))
+    }
+
+    fn application_type(type_name: &str) -> ParameterType {
+        ParameterType::ApplicationType(ApplicationType { type_name: String::from(type_name), module_path: vec![] })
+    }
+
+    #[test]
+    fn parse_type_definition_test() {
+        let record = TypeDefinition::Record(RecordType {
+            name: String::from("User"),
+            fields: vec![
+                TypeField { name: String::from("id"), ftype: application_type("UserId") },
+                TypeField { name: String::from("email"), ftype: application_type("Email") },
+            ],
+        });
+        assert_eq!(parse_type_definition("User { id: UserId, email: Email }"), Ok(record));
+
+        let sum = TypeDefinition::Sum(SumType {
+            name: String::from("Status"),
+            variants: vec![
+                RecordType { name: String::from("Active"), fields: vec![] },
+                RecordType {
+                    name: String::from("Disabled"),
+                    fields: vec![TypeField { name: String::from("reason"), ftype: application_type("String") }],
+                },
+            ],
+        });
+        assert_eq!(parse_type_definition("Status = Active | Disabled { reason: String }"), Ok(sum));
+
+        let alias = TypeDefinition::Alias(AliasType { name: String::from("UserId"), target: application_type("String") });
+        assert_eq!(parse_type_definition("UserId = String"), Ok(alias));
+    }
+
+    #[test]
+    fn invalid_type_definition_test() {
+        let expected = SignatureError {
+            line: 1,
+            column: 6,
+            length: 1,
+            expected: String::from("expected `{` or `=` after the type name"),
+        };
+        assert_eq!(parse_type_definition("User : { id: UserId }"), Err(expected))
+    }
+
+    #[test]
+    fn clashing_variant_error_test() {
+        let expected = SignatureError {
+            line: 1,
+            column: 21,
+            length: 4,
+            expected: String::from("expected a variant which isn't a declared type, or only declared types to declare a union"),
+        };
+        assert_eq!(clashing_variant_error("Principal = Guest | User { token: String }", "User"), expected)
+    }
 }